@@ -0,0 +1,394 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Mul},
+};
+
+/// Each limb holds 9 decimal digits, which keeps Display trivial and still fits a carry in u64
+const BASE: u64 = 1_000_000_000;
+
+/// A minimal arbitrary precision unsigned integer, for when answers outgrow u64
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BigUint {
+    /// Little endian limbs, with no trailing zero limbs (so zero is empty)
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    /// Builds a number from big endian decimal digits
+    pub fn from_digits(digits: &[u32]) -> Self {
+        let mut limbs = Vec::with_capacity(digits.len().div_ceil(9));
+
+        for chunk in digits.rchunks(9) {
+            limbs.push(chunk.iter().fold(0, |acc, &digit| acc * 10 + digit));
+        }
+
+        let mut result = Self { limbs };
+        result.normalise();
+        result
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// At least as many bits as the number needs, counting each limb below the top one as a
+    /// full 30 bits
+    pub fn max_bits(&self) -> u64 {
+        match self.limbs.last() {
+            Some(top) => {
+                (self.limbs.len() as u64 - 1) * 30 + (u32::BITS - top.leading_zeros()) as u64
+            }
+            None => 0,
+        }
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        self.limbs.iter().rev().try_fold(0u64, |acc, &limb| {
+            acc.checked_mul(BASE)?.checked_add(limb as u64)
+        })
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        self.limbs.iter().rev().try_fold(0u128, |acc, &limb| {
+            acc.checked_mul(BASE as u128)?.checked_add(limb as u128)
+        })
+    }
+
+    /// self - other, or None if that would be negative
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if *self < *other {
+            return None;
+        }
+
+        let mut limbs = self.limbs.clone();
+        let mut borrow = 0;
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let subtrahend = other.limbs.get(i).copied().unwrap_or(0) as i64 + borrow;
+            let mut difference = *limb as i64 - subtrahend;
+
+            borrow = 0;
+            if difference < 0 {
+                difference += BASE as i64;
+                borrow = 1;
+            }
+            *limb = difference as u32;
+        }
+
+        let mut result = Self { limbs };
+        result.normalise();
+        Some(result)
+    }
+
+    /// self / other rounded down, with the remainder, or None when dividing by zero.
+    ///
+    /// Schoolbook long division, one limb at a time, binary searching for each limb of the
+    /// quotient.
+    pub fn checked_div_rem(&self, other: &BigUint) -> Option<(BigUint, BigUint)> {
+        if other.is_zero() {
+            return None;
+        }
+
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut remainder = BigUint::zero();
+
+        for (i, &limb) in self.limbs.iter().enumerate().rev() {
+            // remainder = remainder * BASE + limb
+            remainder.limbs.insert(0, limb);
+            remainder.normalise();
+
+            let (mut low, mut high) = (0, BASE as u32 - 1);
+            while low < high {
+                let mid = low + (high - low).div_ceil(2);
+                if other.mul_small(mid) <= remainder {
+                    low = mid;
+                } else {
+                    high = mid - 1;
+                }
+            }
+
+            quotient[i] = low;
+            remainder = remainder.checked_sub(&other.mul_small(low)).unwrap();
+        }
+
+        let mut quotient = Self { limbs: quotient };
+        quotient.normalise();
+        Some((quotient, remainder))
+    }
+
+    /// self to the power of exp, by repeated squaring
+    pub fn pow(&self, mut exp: u64) -> BigUint {
+        let mut result = BigUint::from(1);
+        let mut base = self.clone();
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+
+        result
+    }
+
+    fn mul_small(&self, factor: u32) -> BigUint {
+        let mut limbs = Vec::with_capacity(self.limbs.len() + 1);
+        let mut carry = 0;
+
+        for &limb in &self.limbs {
+            let product = limb as u64 * factor as u64 + carry;
+            limbs.push((product % BASE) as u32);
+            carry = product / BASE;
+        }
+        if carry > 0 {
+            limbs.push(carry as u32);
+        }
+
+        let mut result = Self { limbs };
+        result.normalise();
+        result
+    }
+
+    fn normalise(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(mut value: u64) -> Self {
+        let mut limbs = Vec::new();
+
+        while value > 0 {
+            limbs.push((value % BASE) as u32);
+            value /= BASE;
+        }
+
+        Self { limbs }
+    }
+}
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, other: &BigUint) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+
+        let mut carry = 0;
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let sum = *limb as u64 + other.limbs.get(i).copied().unwrap_or(0) as u64 + carry;
+            *limb = (sum % BASE) as u32;
+            carry = sum / BASE;
+
+            if carry == 0 && i >= other.limbs.len() {
+                break;
+            }
+        }
+
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+}
+
+impl Add<&BigUint> for BigUint {
+    type Output = BigUint;
+
+    fn add(mut self, other: &BigUint) -> BigUint {
+        self += other;
+        self
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    /// Schoolbook multiplication, each limb product plus carries still fits in a u64
+    fn mul(self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+
+        let mut limbs = vec![0u64; self.limbs.len() + other.limbs.len()];
+
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let current = limbs[i + j] + a as u64 * b as u64 + carry;
+                limbs[i + j] = current % BASE;
+                carry = current / BASE;
+            }
+            limbs[i + other.limbs.len()] += carry;
+        }
+
+        let mut result = BigUint {
+            limbs: limbs.into_iter().map(|limb| limb as u32).collect(),
+        };
+        result.normalise();
+        result
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Sum for BigUint {
+    fn sum<I: Iterator<Item = BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::zero(), |acc, value| acc + &value)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((most_significant, rest)) = self.limbs.split_last() else {
+            return f.pad("0");
+        };
+
+        let mut s = most_significant.to_string();
+        for limb in rest.iter().rev() {
+            s.push_str(&format!("{limb:09}"));
+        }
+
+        f.pad(&s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_big_uint_from_digits() {
+        assert_eq!(BigUint::from_digits(&[]), BigUint::zero());
+        assert_eq!(BigUint::from_digits(&[0, 0, 0]), BigUint::zero());
+        assert_eq!(BigUint::from_digits(&[9, 8, 7]), BigUint::from(987));
+        assert_eq!(
+            BigUint::from_digits(&[1, 8, 4, 4, 6, 7, 4, 4, 0, 7, 3, 7, 0, 9, 5, 5, 1, 6, 1, 5]),
+            BigUint::from(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_big_uint_conversions() {
+        assert_eq!(BigUint::zero().max_bits(), 0);
+        assert_eq!(BigUint::from(2).max_bits(), 2);
+        assert!(BigUint::from(u64::MAX).max_bits() >= 64);
+        for exp in [1, 10, 100, 1000] {
+            assert!(BigUint::from(2).pow(exp).max_bits() > exp);
+        }
+
+        assert_eq!(BigUint::from(u64::MAX).to_u128(), Some(u64::MAX as u128));
+        let big = &BigUint::from(u64::MAX) * &BigUint::from(u64::MAX);
+        assert_eq!(big.to_u64(), None);
+        assert_eq!(big.to_u128(), Some(u64::MAX as u128 * u64::MAX as u128));
+        assert_eq!((&big * &BigUint::from(u64::MAX)).to_u128(), None);
+    }
+
+    #[test]
+    fn test_big_uint_display() {
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!(BigUint::from(1_000_000_000).to_string(), "1000000000");
+        assert_eq!(BigUint::from(u64::MAX).to_string(), u64::MAX.to_string());
+
+        let digits = [1; 30];
+        assert_eq!(
+            BigUint::from_digits(&digits).to_string(),
+            "111111111111111111111111111111"
+        );
+    }
+
+    #[test]
+    fn test_big_uint_add() {
+        assert_eq!(
+            BigUint::from(999_999_999) + &BigUint::from(1),
+            BigUint::from(1_000_000_000)
+        );
+        assert_eq!(
+            (BigUint::from(u64::MAX) + &BigUint::from(u64::MAX)).to_string(),
+            (u64::MAX as u128 * 2).to_string()
+        );
+        assert_eq!(
+            BigUint::from(5) + &BigUint::from_digits(&[9; 20]),
+            BigUint::from_digits(&[
+                1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4
+            ])
+        );
+        assert_eq!(
+            [1, 2, 3].map(BigUint::from).into_iter().sum::<BigUint>(),
+            BigUint::from(6)
+        );
+    }
+
+    fn big(s: &str) -> BigUint {
+        let digits: Vec<u32> = s.chars().map(|c| c.to_digit(10).unwrap()).collect();
+        BigUint::from_digits(&digits)
+    }
+
+    #[test]
+    fn test_big_uint_mul_and_pow() {
+        assert_eq!(
+            (&BigUint::from(u64::MAX) * &BigUint::from(u64::MAX)).to_string(),
+            "340282366920938463426481119284349108225"
+        );
+        assert_eq!(&BigUint::zero() * &BigUint::from(5), BigUint::zero());
+        assert_eq!(
+            BigUint::from(2).pow(100).to_string(),
+            "1267650600228229401496703205376"
+        );
+        assert_eq!(BigUint::from(7).pow(0), BigUint::from(1));
+    }
+
+    #[test]
+    fn test_big_uint_sub_div_and_ord() {
+        let a = big("1267650600228229401496703205376");
+        let b = big("340282366920938463426481119284349108225");
+
+        assert!(a < b);
+        assert!(BigUint::from(1_000_000_000) > BigUint::from(999_999_999));
+        assert_eq!(a.checked_sub(&b), None);
+        assert_eq!(
+            b.checked_sub(&a).unwrap().to_string(),
+            "340282365653287863198251717787645902849"
+        );
+        assert_eq!(a.checked_sub(&a), Some(BigUint::zero()));
+
+        let (quotient, remainder) = b.checked_div_rem(&a).unwrap();
+        assert_eq!(quotient.to_string(), "268435455");
+        assert_eq!(remainder.to_string(), "1267650600191335913349284102145");
+        assert_eq!(
+            BigUint::from(2)
+                .pow(100)
+                .checked_div_rem(&BigUint::from(2).pow(99)),
+            Some((BigUint::from(2), BigUint::zero()))
+        );
+        assert_eq!(
+            BigUint::from(17).checked_div_rem(&BigUint::from(20)),
+            Some((BigUint::zero(), BigUint::from(17)))
+        );
+        assert_eq!(a.checked_div_rem(&BigUint::zero()), None);
+
+        assert_eq!(BigUint::from(u64::MAX).to_u64(), Some(u64::MAX));
+        assert_eq!((BigUint::from(u64::MAX) + &BigUint::from(1)).to_u64(), None);
+    }
+}
@@ -1,47 +1,168 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Write},
+    num::ParseIntError,
+    str::FromStr,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 enum Rotation {
-    Left(u32),
-    Right(u32),
+    Left(u64),
+    Right(u64),
 }
 
 impl Rotation {
-    fn change(&self) -> i32 {
+    /// i128 so that any u64 rotation, added to any position, can't overflow
+    fn change(&self) -> i128 {
         match self {
-            Rotation::Left(n) => -(*n as i32),
-            Rotation::Right(n) => *n as i32,
+            Rotation::Left(n) => -(*n as i128),
+            Rotation::Right(n) => *n as i128,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ParseRotationError {
+    Empty,
+    UnknownDirection(char),
+    InvalidNumber(ParseIntError),
+}
+
+impl FromStr for Rotation {
+    type Err = ParseRotationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut chars = value.chars();
+        let direction = chars.next().ok_or(ParseRotationError::Empty)?;
+        let number = chars
+            .as_str()
+            .parse()
+            .map_err(ParseRotationError::InvalidNumber)?;
+
+        match direction {
+            'L' => Ok(Rotation::Left(number)),
+            'R' => Ok(Rotation::Right(number)),
+            _ => Err(ParseRotationError::UnknownDirection(direction)),
         }
     }
 }
 
 impl From<&str> for Rotation {
     fn from(value: &str) -> Self {
-        let direction = &value[0..1];
-        let number = value[1..].parse().expect("valid number");
+        value
+            .parse()
+            .unwrap_or_else(|e| panic!("invalid rotation {value:?}: {e:?}"))
+    }
+}
 
-        match direction {
-            "L" => Rotation::Left(number),
-            "R" => Rotation::Right(number),
-            _ => panic!("unknown direction: {0}", direction),
+impl fmt::Display for Rotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rotation::Left(n) => write!(f, "L{n}"),
+            Rotation::Right(n) => write!(f, "R{n}"),
         }
     }
 }
 
+/// What a single rotation did to the lock
+#[derive(Debug, PartialEq)]
+struct TraceStep {
+    rotation: Rotation,
+    start: i64,
+    end: i64,
+    /// Times zero was passed or landed on during this rotation, as counted by the rotate fn
+    zeros: u128,
+    /// The password after this rotation
+    password: u128,
+}
+
+struct Trace {
+    steps: Vec<TraceStep>,
+}
+
+impl Trace {
+    const HEADERS: [&str; 5] = ["rotation", "start", "end", "zeros", "password"];
+
+    fn rows(&self) -> impl Iterator<Item = [String; 5]> {
+        self.steps.iter().map(|step| {
+            [
+                step.rotation.to_string(),
+                step.start.to_string(),
+                step.end.to_string(),
+                step.zeros.to_string(),
+                step.password.to_string(),
+            ]
+        })
+    }
+
+    /// Renders the trace as an aligned, human readable table
+    fn table(&self) -> String {
+        let mut widths = Self::HEADERS.map(str::len);
+        for row in self.rows() {
+            for (width, cell) in widths.iter_mut().zip(&row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        let mut out = String::new();
+        let headers = Self::HEADERS.map(str::to_owned);
+
+        for row in std::iter::once(headers).chain(self.rows()) {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:>width$}"))
+                .collect();
+            writeln!(out, "{}", cells.join(" | ")).unwrap();
+        }
+
+        out
+    }
+
+    /// Renders the trace as CSV, with a header row
+    fn csv(&self) -> String {
+        let mut out = Self::HEADERS.join(",");
+        out.push('\n');
+
+        for row in self.rows() {
+            out.push_str(&row.join(","));
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+#[derive(Clone)]
 struct RotaryLock {
-    position: i32,
-    max: i32,
-    rotations: u32,
+    position: i64,
+    max: i64,
+    /// u128 as a single u64 rotation can already pass zero u64::MAX times
+    rotations: u128,
 }
 
 impl RotaryLock {
     fn new() -> Self {
+        Self::with_dial(100, 50)
+    }
+
+    /// A lock whose dial has `size` positions (0 to size - 1), starting at `position`
+    fn with_dial(size: u32, position: u32) -> Self {
+        assert!(size > 0, "dial must have at least one position");
+        assert!(
+            position < size,
+            "starting position {position} is off a dial of size {size}"
+        );
+
         Self {
-            position: 50,
-            max: 100,
+            position: position as i64,
+            max: size as i64,
             rotations: 0,
         }
     }
 
     fn rotate_1(&mut self, r: &Rotation) {
-        self.position = (self.position + r.change()).rem_euclid(self.max);
+        self.position = (self.position as i128 + r.change()).rem_euclid(self.max as i128) as i64;
 
         if self.position == 0 {
             self.rotations += 1;
@@ -49,10 +170,9 @@ impl RotaryLock {
     }
 
     fn rotate_2(&mut self, r: &Rotation) {
-        let new_position = (self.position + r.change()).rem_euclid(self.max);
-        let mut new_rotations = (self.position + r.change())
-            .div_euclid(self.max)
-            .unsigned_abs();
+        let moved_to = self.position as i128 + r.change();
+        let new_position = moved_to.rem_euclid(self.max as i128) as i64;
+        let mut new_rotations = moved_to.div_euclid(self.max as i128).unsigned_abs();
 
         if let Rotation::Left(_) = r {
             if new_position == 0 {
@@ -68,13 +188,233 @@ impl RotaryLock {
         self.rotations += new_rotations;
     }
 
-    fn password(&self) -> u32 {
+    fn password(&self) -> u128 {
         self.rotations
     }
+
+    /// Applies each rotation with `rotate`, recording where the dial started and ended and
+    /// how much the password went up by for each one
+    fn trace(&mut self, rotations: &[Rotation], rotate: fn(&mut RotaryLock, &Rotation)) -> Trace {
+        let steps = rotations
+            .iter()
+            .map(|&rotation| {
+                let start = self.position;
+                let password_before = self.password();
+
+                rotate(self, &rotation);
+
+                TraceStep {
+                    rotation,
+                    start,
+                    end: self.position,
+                    zeros: self.password() - password_before,
+                    password: self.password(),
+                }
+            })
+            .collect();
+
+        Trace { steps }
+    }
+}
+
+/// A rotation aimed at one dial of a CombinationLock, written as `dial:rotation` with a
+/// zero-based dial index, e.g. `2:L15`
+#[derive(Debug, PartialEq)]
+struct DialRotation {
+    dial: usize,
+    rotation: Rotation,
+}
+
+#[derive(Debug, PartialEq)]
+enum ParseDialRotationError {
+    /// No `:` between the dial and the rotation
+    MissingColon,
+    InvalidDial(ParseIntError),
+    InvalidRotation(ParseRotationError),
+}
+
+impl FromStr for DialRotation {
+    type Err = ParseDialRotationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (dial, rotation) = value
+            .split_once(':')
+            .ok_or(ParseDialRotationError::MissingColon)?;
+
+        Ok(Self {
+            dial: dial.parse().map_err(ParseDialRotationError::InvalidDial)?,
+            rotation: rotation
+                .parse()
+                .map_err(ParseDialRotationError::InvalidRotation)?,
+        })
+    }
+}
+
+impl From<&str> for DialRotation {
+    fn from(value: &str) -> Self {
+        value
+            .parse()
+            .unwrap_or_else(|e| panic!("invalid dial rotation {value:?}: {e:?}"))
+    }
+}
+
+/// Several independent dials, each counting zeros the way rotate_2 does
+struct CombinationLock {
+    dials: Vec<RotaryLock>,
+}
+
+impl CombinationLock {
+    /// One dial per (size, starting position) pair
+    fn new(dials: &[(u32, u32)]) -> Self {
+        Self {
+            dials: dials
+                .iter()
+                .map(|&(size, position)| RotaryLock::with_dial(size, position))
+                .collect(),
+        }
+    }
+
+    fn rotate(&mut self, r: &DialRotation) {
+        let dial_count = self.dials.len();
+        let dial = self
+            .dials
+            .get_mut(r.dial)
+            .unwrap_or_else(|| panic!("no dial {} on a lock with {dial_count} dials", r.dial));
+
+        dial.rotate_2(&r.rotation);
+    }
+
+    /// The zeros passed or landed on by each dial
+    fn dial_passwords(&self) -> Vec<u128> {
+        self.dials.iter().map(RotaryLock::password).collect()
+    }
+
+    /// The zeros passed or landed on across all dials
+    fn password(&self) -> u128 {
+        self.dials.iter().map(RotaryLock::password).sum()
+    }
+}
+
+/// The most rotations synthesise_rotations will try, searches that need more are refused
+const MAX_SYNTHESIS_ROTATIONS: u64 = 1 << 24;
+
+/// Why synthesise_rotations didn't find a sequence
+#[derive(Debug, PartialEq)]
+enum SynthesisError {
+    /// No sequence of at most `max_len` rotations gives the target password
+    Unreachable,
+    /// The search would try more than MAX_SYNTHESIS_ROTATIONS rotations, so it wasn't run to
+    /// the end
+    SearchTooLarge,
+}
+
+/// Finds a shortest sequence of at most `max_len` rotations that takes a lock with a dial of
+/// `size` positions from `start` to a password of exactly `target`, under the semantics of
+/// `rotate`. Handy for building fixtures for the lock logic.
+///
+/// Breadth first search over (position, password) states. A rotation of `k * size + r`, with
+/// `r` in 1..=size, ends where a rotation of `r` does, and each of the `k` whole turns adds
+/// the same number of zeros. So only the `2 * size` rotations without whole turns are tried
+/// from each state, along with the one number of whole turns (if any) that lands exactly on
+/// the target, which is worked out from the zeros of `r` and `size + r` rather than searched
+/// for. That's 4 calls to `rotate` per state and remainder, and the search stops with
+/// SearchTooLarge before starting a round that would take it past MAX_SYNTHESIS_ROTATIONS.
+fn synthesise_rotations(
+    size: u32,
+    start: u32,
+    target: u64,
+    max_len: usize,
+    rotate: fn(&mut RotaryLock, &Rotation),
+) -> Result<Vec<Rotation>, SynthesisError> {
+    let target = target as u128;
+    let size = size as u64;
+    let start_lock = RotaryLock::with_dial(size as u32, start);
+    let state = |lock: &RotaryLock| (lock.position, lock.password());
+    let turned = |lock: &RotaryLock, rotation: &Rotation| {
+        let mut next = lock.clone();
+        rotate(&mut next, rotation);
+        next
+    };
+
+    // For every reached state, the state it was reached from and the rotation that did it
+    let mut came_from: HashMap<(i64, u128), ((i64, u128), Rotation)> = HashMap::new();
+    let mut seen = HashSet::from([state(&start_lock)]);
+    let mut tried = 0;
+
+    let mut frontier = vec![start_lock];
+    let mut found = (target == 0).then(|| state(&frontier[0]));
+
+    for _ in 0..max_len {
+        if found.is_some() || frontier.is_empty() {
+            break;
+        }
+
+        tried = (frontier.len() as u64)
+            .saturating_mul(4 * size)
+            .saturating_add(tried);
+        if tried > MAX_SYNTHESIS_ROTATIONS {
+            return Err(SynthesisError::SearchTooLarge);
+        }
+
+        let mut next_frontier = Vec::new();
+
+        'frontier: for lock in &frontier {
+            for r in 1..=size {
+                for direction in [Rotation::Left, Rotation::Right] {
+                    let rotation = direction(r);
+                    let next = turned(lock, &rotation);
+                    let per_turn = turned(lock, &direction(size + r))
+                        .password()
+                        .saturating_sub(next.password());
+
+                    // The whole turns that make up the rest of the target, if they fit in a u64
+                    let finishing = target
+                        .checked_sub(next.password())
+                        .filter(|&missing| per_turn > 0 && missing > 0 && missing % per_turn == 0)
+                        .and_then(|missing| u64::try_from(missing / per_turn).ok())
+                        .and_then(|turns| turns.checked_mul(size)?.checked_add(r))
+                        .map(|magnitude| {
+                            let rotation = direction(magnitude);
+                            (turned(lock, &rotation), rotation)
+                        });
+
+                    for (next, rotation) in std::iter::once((next, rotation)).chain(finishing) {
+                        if next.password() > target || !seen.insert(state(&next)) {
+                            continue;
+                        }
+
+                        came_from.insert(state(&next), (state(lock), rotation));
+
+                        if next.password() == target {
+                            found = Some(state(&next));
+                            break 'frontier;
+                        }
+
+                        next_frontier.push(next);
+                    }
+                }
+            }
+        }
+
+        frontier = next_frontier;
+    }
+
+    let mut current = found.ok_or(SynthesisError::Unreachable)?;
+    let mut rotations = Vec::new();
+
+    while let Some(&(previous, rotation)) = came_from.get(&current) {
+        rotations.push(rotation);
+        current = previous;
+    }
+
+    rotations.reverse();
+    Ok(rotations)
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::Rng;
+
     use super::*;
 
     fn parse_rotations(data: &str) -> Vec<Rotation> {
@@ -85,7 +425,7 @@ mod tests {
             .collect()
     }
 
-    fn get_password(data: &str, rotate: fn(&mut RotaryLock, &Rotation)) -> u32 {
+    fn get_password(data: &str, rotate: fn(&mut RotaryLock, &Rotation)) -> u128 {
         let rotations = parse_rotations(data);
         let mut rotary_lock = RotaryLock::new();
 
@@ -96,6 +436,312 @@ mod tests {
         rotary_lock.password()
     }
 
+    /// Turns the dial one click at a time, counting every time it points at zero
+    fn click_by_click(position: i64, size: i64, r: &Rotation) -> (i64, u128) {
+        let step = r.change().signum() as i64;
+        let mut position = position;
+        let mut zeros = 0;
+
+        for _ in 0..r.change().abs() {
+            position = (position + step).rem_euclid(size);
+            if position == 0 {
+                zeros += 1;
+            }
+        }
+
+        (position, zeros)
+    }
+
+    #[test]
+    fn test_day_1_with_dial() {
+        let mut lock = RotaryLock::with_dial(10, 3);
+
+        lock.rotate_2(&Rotation::Left(3));
+        assert_eq!((lock.position, lock.password()), (0, 1));
+
+        lock.rotate_2(&Rotation::Left(25));
+        assert_eq!((lock.position, lock.password()), (5, 3));
+
+        lock.rotate_2(&Rotation::Right(15));
+        assert_eq!((lock.position, lock.password()), (0, 5));
+    }
+
+    #[test]
+    fn test_day_1_rotations_match_click_by_click() {
+        let mut rng = Rng(0x2025_0001);
+
+        for size in 1..=12 {
+            for start in 0..size {
+                let mut lock_1 = RotaryLock::with_dial(size, start);
+                let mut lock_2 = RotaryLock::with_dial(size, start);
+                let mut expected_1 = 0;
+                let mut expected_2 = 0;
+
+                for _ in 0..200 {
+                    let n = rng.below(4 * size as u64);
+                    let r = if rng.below(2) == 0 {
+                        Rotation::Left(n)
+                    } else {
+                        Rotation::Right(n)
+                    };
+
+                    let (position, zeros) = click_by_click(lock_2.position, size as i64, &r);
+                    if position == 0 {
+                        expected_1 += 1;
+                    }
+                    expected_2 += zeros;
+
+                    lock_1.rotate_1(&r);
+                    lock_2.rotate_2(&r);
+
+                    assert_eq!(lock_1.position, position);
+                    assert_eq!(lock_2.position, position);
+                    assert_eq!(lock_1.password(), expected_1);
+                    assert_eq!(lock_2.password(), expected_2);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_day_1_trace() {
+        let rotations = parse_rotations(include_str!("assets/day_1_sample.txt"));
+        let trace = RotaryLock::new().trace(&rotations, RotaryLock::rotate_2);
+
+        assert_eq!(
+            trace.steps[0],
+            TraceStep {
+                rotation: Rotation::Left(68),
+                start: 50,
+                end: 82,
+                zeros: 1,
+                password: 1
+            }
+        );
+        assert_eq!(
+            trace.steps.iter().map(|s| s.zeros).collect::<Vec<_>>(),
+            vec![1, 0, 1, 0, 1, 1, 0, 1, 0, 1]
+        );
+        assert_eq!(trace.steps.last().unwrap().password, 6);
+
+        let table = trace.table();
+        let mut lines = table.lines();
+        assert_eq!(
+            lines.next(),
+            Some("rotation | start | end | zeros | password")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("     L68 |    50 |  82 |     1 |        1")
+        );
+
+        let csv = trace.csv();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("rotation,start,end,zeros,password"));
+        assert_eq!(lines.next(), Some("L68,50,82,1,1"));
+        assert_eq!(lines.last(), Some("L82,14,32,1,6"));
+    }
+
+    #[test]
+    fn test_day_1_synthesise_rotations() {
+        let rotates: [fn(&mut RotaryLock, &Rotation); 2] =
+            [RotaryLock::rotate_1, RotaryLock::rotate_2];
+
+        for (size, start) in [(100, 50), (10, 0), (7, 3), (1, 0)] {
+            for target in 0..=6 {
+                for rotate in rotates {
+                    let rotations = synthesise_rotations(size, start, target, 10, rotate).unwrap();
+
+                    let mut lock = RotaryLock::with_dial(size, start);
+                    for r in &rotations {
+                        rotate(&mut lock, r);
+                    }
+                    assert_eq!(lock.password(), target as u128);
+                }
+
+                // At most one zero per rotation when only landing on it counts...
+                let rotations =
+                    synthesise_rotations(size, start, target, 10, RotaryLock::rotate_1).unwrap();
+                assert_eq!(rotations.len(), target as usize);
+
+                // ...but passing zero counts too, so one big enough turn always does it
+                let rotations =
+                    synthesise_rotations(size, start, target, 10, RotaryLock::rotate_2).unwrap();
+                assert_eq!(rotations.len(), target.min(1) as usize);
+            }
+        }
+
+        assert_eq!(
+            synthesise_rotations(100, 50, 5, 4, RotaryLock::rotate_1),
+            Err(SynthesisError::Unreachable)
+        );
+        assert_eq!(
+            synthesise_rotations(100, 50, 40, 10, RotaryLock::rotate_2),
+            Ok(vec![Rotation::Left(4001)])
+        );
+        assert_eq!(
+            synthesise_rotations(100, 50, 40, 40, RotaryLock::rotate_1).map(|r| r.len()),
+            Ok(40)
+        );
+        assert_eq!(
+            synthesise_rotations(1_000_000, 0, 1, 10, RotaryLock::rotate_2),
+            Ok(vec![Rotation::Left(1_000_001)])
+        );
+        assert_eq!(
+            synthesise_rotations(1, 0, u64::MAX, 10, RotaryLock::rotate_2),
+            Ok(vec![Rotation::Left(u64::MAX)])
+        );
+        assert_eq!(
+            synthesise_rotations(u32::MAX, 0, 1, 10, RotaryLock::rotate_2),
+            Err(SynthesisError::SearchTooLarge)
+        );
+        assert_eq!(
+            synthesise_rotations(100, 50, 3, 3, RotaryLock::rotate_1),
+            Ok(vec![
+                Rotation::Left(50),
+                Rotation::Left(100),
+                Rotation::Left(100)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_day_1_combination_lock() {
+        let mut lock = CombinationLock::new(&[(100, 50), (10, 3), (1, 0)]);
+
+        for line in ["0:L68", "1:L3", "0:R32", "1:R27", "2:L5", "0:L100"] {
+            lock.rotate(&DialRotation::from(line));
+        }
+
+        assert_eq!(lock.dial_passwords(), vec![3, 3, 5]);
+        assert_eq!(lock.password(), 11);
+        assert_eq!(
+            lock.dials.iter().map(|d| d.position).collect::<Vec<_>>(),
+            vec![14, 7, 0]
+        );
+    }
+
+    #[test]
+    fn test_day_1_combination_lock_matches_single_dial() {
+        let mut lock = CombinationLock::new(&[(100, 50)]);
+
+        for line in include_str!("assets/day_1_sample.txt").lines() {
+            lock.rotate(&DialRotation::from(format!("0:{line}").as_str()));
+        }
+
+        assert_eq!(lock.password(), 6);
+    }
+
+    #[test]
+    fn test_day_1_combination_lock_huge_password() {
+        let mut lock = CombinationLock::new(&[(1, 0), (1, 0)]);
+
+        lock.rotate(&DialRotation::from(format!("0:L{}", u64::MAX).as_str()));
+        lock.rotate(&DialRotation::from(format!("1:R{}", u64::MAX).as_str()));
+
+        assert_eq!(lock.password(), 2 * u64::MAX as u128);
+    }
+
+    #[test]
+    #[should_panic(expected = "no dial 3 on a lock with 2 dials")]
+    fn test_day_1_combination_lock_unknown_dial() {
+        CombinationLock::new(&[(100, 50), (10, 0)]).rotate(&DialRotation::from("3:R1"));
+    }
+
+    #[test]
+    fn test_day_1_large_rotations() {
+        let mut lock = RotaryLock::new();
+        lock.rotate_2(&Rotation::from("R3000000000"));
+        assert_eq!((lock.position, lock.password()), (50, 30_000_000));
+
+        lock.rotate_2(&Rotation::from("L3000000050"));
+        assert_eq!((lock.position, lock.password()), (0, 60_000_001));
+
+        let mut lock = RotaryLock::with_dial(1, 0);
+        lock.rotate_2(&Rotation::Left(u64::MAX));
+        assert_eq!((lock.position, lock.password()), (0, u64::MAX as u128));
+
+        let mut lock = RotaryLock::with_dial(u32::MAX, u32::MAX - 1);
+        lock.rotate_2(&Rotation::Right(u64::MAX));
+        assert_eq!(lock.password(), (1 << 32) + 1);
+
+        // Enough to take the password past u64::MAX
+        let mut lock = RotaryLock::new();
+        for _ in 0..101 {
+            lock.rotate_2(&Rotation::from("R18446744073709551615"));
+        }
+        assert_eq!(lock.password(), (50 + 101 * u64::MAX as u128) / 100);
+
+        let mut lock = RotaryLock::new();
+        lock.rotate_1(&Rotation::Left(u64::MAX - 49));
+        assert_eq!(
+            lock.position,
+            (50 - (u64::MAX - 49) as i128).rem_euclid(100) as i64
+        );
+    }
+
+    #[test]
+    fn test_day_1_parse_rotation() {
+        assert_eq!(
+            "L18446744073709551615".parse(),
+            Ok(Rotation::Left(u64::MAX))
+        );
+        assert_eq!("".parse::<Rotation>(), Err(ParseRotationError::Empty));
+        assert_eq!(
+            "X5".parse::<Rotation>(),
+            Err(ParseRotationError::UnknownDirection('X'))
+        );
+        assert!(matches!(
+            "R18446744073709551616".parse::<Rotation>(),
+            Err(ParseRotationError::InvalidNumber(_))
+        ));
+        assert!(matches!(
+            "R-5".parse::<Rotation>(),
+            Err(ParseRotationError::InvalidNumber(_))
+        ));
+    }
+
+    #[test]
+    fn test_day_1_parse_dial_rotation() {
+        assert_eq!(
+            "2:L15".parse(),
+            Ok(DialRotation {
+                dial: 2,
+                rotation: Rotation::Left(15)
+            })
+        );
+        assert_eq!(
+            "L15".parse::<DialRotation>(),
+            Err(ParseDialRotationError::MissingColon)
+        );
+        assert!(matches!(
+            "x:L15".parse::<DialRotation>(),
+            Err(ParseDialRotationError::InvalidDial(_))
+        ));
+        assert!(matches!(
+            "-1:L15".parse::<DialRotation>(),
+            Err(ParseDialRotationError::InvalidDial(_))
+        ));
+        assert_eq!(
+            "0:".parse::<DialRotation>(),
+            Err(ParseDialRotationError::InvalidRotation(
+                ParseRotationError::Empty
+            ))
+        );
+        assert_eq!(
+            "0:X5".parse::<DialRotation>(),
+            Err(ParseDialRotationError::InvalidRotation(
+                ParseRotationError::UnknownDirection('X')
+            ))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid dial rotation \"L15\": MissingColon")]
+    fn test_day_1_dial_rotation_from_invalid_str() {
+        CombinationLock::new(&[(100, 50)]).rotate(&DialRotation::from("L15"));
+    }
+
     #[test]
     fn test_day_1_part_1_sample() {
         let password = get_password(
@@ -16,7 +16,7 @@ impl Machine {
             .map(Machine::parse_button)
             .collect();
 
-        let joltages = parts.skip(buttons.len()).next().unwrap();
+        let joltages = parts.nth(buttons.len()).unwrap();
         let joltages = joltages[1..joltages.len() - 1]
             .split(",")
             .map(|num| num.parse::<u16>().unwrap())
@@ -110,6 +110,42 @@ impl Machine {
     }
 }
 
+// I didn't come up with this magic: https://rosettacode.org/wiki/Gosper%27s_hack
+struct GosperIterator {
+    width: u8,
+    k: u8,
+    x: u32,
+}
+
+impl GosperIterator {
+    fn new(width: u8) -> Self {
+        Self { width, k: 1, x: 1 }
+    }
+}
+
+impl Iterator for GosperIterator {
+    type Item = (u32, u8);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.k <= self.width {
+            if self.x < (1 << self.width) {
+                let result = self.x;
+
+                let c = self.x & (!self.x + 1);
+                let r = self.x + c;
+                self.x = (((r ^ self.x) >> 2) / c) | r;
+
+                return Some((result, self.k));
+            }
+
+            self.k += 1;
+            self.x = (1 << self.k) - 1;
+        }
+
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::bench;
@@ -188,39 +224,3 @@ mod tests {
         assert_eq!(gospers_iter.next(), None);
     }
 }
-
-// I didn't come up with this magic: https://rosettacode.org/wiki/Gosper%27s_hack
-struct GosperIterator {
-    width: u8,
-    k: u8,
-    x: u32,
-}
-
-impl GosperIterator {
-    fn new(width: u8) -> Self {
-        Self { width, k: 1, x: 1 }
-    }
-}
-
-impl Iterator for GosperIterator {
-    type Item = (u32, u8);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        while self.k <= self.width {
-            if self.x < (1 << self.width) {
-                let result = self.x;
-
-                let c = self.x & (!self.x + 1);
-                let r = self.x + c;
-                self.x = (((r ^ self.x) >> 2) / c) | r;
-
-                return Some((result, self.k));
-            }
-
-            self.k += 1;
-            self.x = (1 << self.k) - 1;
-        }
-
-        None
-    }
-}
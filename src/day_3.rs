@@ -1,64 +1,202 @@
-fn digits_to_num(digits: &[u32]) -> u64 {
-    let mut result: u64 = 0;
-    let mut pow: u64 = 1;
-    for &digit in digits.iter().rev() {
-        result += digit as u64 * pow;
-        pow *= 10;
+use std::io::{self, BufRead};
+
+use crate::big_uint::BigUint;
+
+fn digits_to_num(digits: &[u32]) -> BigUint {
+    BigUint::from_digits(digits)
+}
+
+struct Joltage {
+    value: BigUint,
+    indices: Vec<usize>,
+}
+
+impl Joltage {
+    fn from_indices(bank: &[u32], indices: Vec<usize>) -> Self {
+        let digits: Vec<u32> = indices.iter().map(|&i| bank[i]).collect();
+
+        Self {
+            value: digits_to_num(&digits),
+            indices,
+        }
     }
+}
 
-    result
+/// Picks `k` digits from the bank, keeping their order, to make the largest possible number.
+fn max_joltage(bank: &[u32], k: usize) -> Joltage {
+    Joltage::from_indices(bank, monotonic_select(bank, k, |top, digit| top < digit))
 }
 
-fn part_1(bank: &[u32]) -> u64 {
-    let (start_index, &first_digit) = bank[0..bank.len() - 1]
+/// Picks `k` digits from the bank, keeping their order, to make the smallest possible number.
+///
+/// When leading zeros aren't allowed the first digit has to be non-zero, so there may be no
+/// valid selection at all.
+fn min_joltage(bank: &[u32], k: usize, allow_leading_zero: bool) -> Option<Joltage> {
+    if allow_leading_zero || k == 0 {
+        return Some(Joltage::from_indices(
+            bank,
+            monotonic_select(bank, k, |top, digit| top > digit),
+        ));
+    }
+
+    assert!(
+        k <= bank.len(),
+        "can't pick {k} digits from a bank of {}",
+        bank.len()
+    );
+
+    // The first digit is the smallest non-zero one that still leaves room for the rest
+    let (first, _) = bank[..=bank.len() - k]
         .iter()
         .enumerate()
-        .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(&a.0)))
-        .unwrap();
-    let second_digit = bank[start_index + 1..].iter().max().unwrap();
+        .filter(|(_, digit)| **digit != 0)
+        .min_by(|a, b| a.1.cmp(b.1).then(a.0.cmp(&b.0)))?;
+
+    let mut indices = vec![first];
+    indices.extend(
+        monotonic_select(&bank[first + 1..], k - 1, |top, digit| top > digit)
+            .into_iter()
+            .map(|i| i + first + 1),
+    );
 
-    digits_to_num(&[first_digit, *second_digit])
+    Some(Joltage::from_indices(bank, indices))
 }
 
-fn part_2(bank: &[u32]) -> u64 {
-    let mut digits = Vec::<u32>::new();
-    let mut starting_from_index = 0;
+/// Finds the `n`th largest (1 based) distinct value that can be made by picking `k` digits
+/// from the bank, or None if there aren't that many distinct values.
+///
+/// Counts the distinct subsequences of each length in every suffix of the bank, then walks
+/// the digits greedily from 9 down, skipping over whole blocks of values that are too large.
+fn nth_largest_joltage(bank: &[u32], k: usize, n: u64) -> Option<Joltage> {
+    if n == 0 || k > bank.len() {
+        return None;
+    }
+
+    // next[i][d] is the first index >= i holding digit d
+    let mut next = vec![[None; 10]; bank.len() + 1];
+    for i in (0..bank.len()).rev() {
+        next[i] = next[i + 1];
+        next[i][bank[i] as usize] = Some(i);
+    }
+
+    // distinct[i][len] is the number of distinct subsequences of length len in bank[i..],
+    // saturating as we only ever need to compare it against n
+    let mut distinct = vec![vec![0u64; k + 1]; bank.len() + 1];
+    for i in (0..=bank.len()).rev() {
+        distinct[i][0] = 1;
+        for len in 1..=k {
+            distinct[i][len] = next[i].iter().flatten().fold(0, |acc: u64, &p| {
+                acc.saturating_add(distinct[p + 1][len - 1])
+            });
+        }
+    }
+
+    if distinct[0][k] < n {
+        return None;
+    }
+
+    let mut remaining = n;
+    let mut from = 0;
+    let mut indices = Vec::with_capacity(k);
 
-    let indexed_bank = bank.iter().enumerate().collect::<Vec<(usize, &u32)>>();
+    for len in (1..=k).rev() {
+        for digit in (0..10).rev() {
+            let Some(p) = next[from][digit] else {
+                continue;
+            };
 
-    while digits.len() < 12 {
-        let remaining = &indexed_bank[starting_from_index..];
-        let further_digits_required = 12 - digits.len() - 1;
-        let window: usize = remaining.len() - further_digits_required;
-        let possibilities = &remaining[0..window];
-        let (index, digit) = possibilities
-            .iter()
-            .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(&a.0)))
-            .unwrap();
+            let count = distinct[p + 1][len - 1];
+            if remaining <= count {
+                indices.push(p);
+                from = p + 1;
+                break;
+            }
 
-        digits.push(**digit);
-        starting_from_index = index + 1;
+            remaining -= count;
+        }
     }
 
-    digits_to_num(&digits)
+    Some(Joltage::from_indices(bank, indices))
 }
 
-fn total_output_joltage(data: &str, part: fn(bank: &[u32]) -> u64) -> u64 {
-    data.lines()
-        .map(str::trim)
-        .filter(|l| !l.is_empty())
-        .map(|line| {
-            line.chars()
-                .map(|digit| digit.to_digit(10).unwrap())
-                .collect::<Vec<u32>>()
-        })
-        .map(|bank| part(&bank))
-        .sum()
+/// Greedily keeps `k` indices, letting each digit knock the top of the stack off while
+/// `replaces(top, digit)` holds and there are still enough digits left to fill the
+/// remaining places.
+fn monotonic_select(bank: &[u32], k: usize, replaces: impl Fn(u32, u32) -> bool) -> Vec<usize> {
+    assert!(
+        k <= bank.len(),
+        "can't pick {k} digits from a bank of {}",
+        bank.len()
+    );
+
+    let mut indices: Vec<usize> = Vec::with_capacity(k);
+
+    for (i, &digit) in bank.iter().enumerate() {
+        while let Some(&top) = indices.last()
+            && replaces(bank[top], digit)
+            && indices.len() + (bank.len() - i) > k
+        {
+            indices.pop();
+        }
+
+        if indices.len() < k {
+            indices.push(i);
+        }
+    }
+
+    indices
+}
+
+fn part_1(bank: &[u32]) -> BigUint {
+    max_joltage(bank, 2).value
+}
+
+fn part_2(bank: &[u32]) -> BigUint {
+    max_joltage(bank, 12).value
+}
+
+fn total_output_joltage(data: &str, part: impl Fn(&[u32]) -> BigUint) -> BigUint {
+    total_output_joltage_streaming(data.as_bytes(), part).expect("valid banks")
+}
+
+/// Same as total_output_joltage, but reads the banks a line at a time, reusing the line and
+/// bank buffers, so memory use stays constant however large the input is.
+fn total_output_joltage_streaming(
+    mut reader: impl BufRead,
+    part: impl Fn(&[u32]) -> BigUint,
+) -> io::Result<BigUint> {
+    let mut line = Vec::new();
+    let mut bank = Vec::new();
+    let mut total = BigUint::zero();
+
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+
+        bank.clear();
+        for &ch in line.trim_ascii() {
+            if !ch.is_ascii_digit() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid joltage digit: {:?}", ch as char),
+                ));
+            }
+            bank.push((ch - b'0') as u32);
+        }
+
+        if !bank.is_empty() {
+            total += &part(&bank);
+        }
+    }
+
+    Ok(total)
 }
 
 #[cfg(test)]
 mod tests {
-    use std::time;
+    use std::{io::BufReader, time};
 
     use super::*;
 
@@ -66,7 +204,7 @@ mod tests {
     fn test_day_3_part_1_sample() {
         assert_eq!(
             total_output_joltage(include_str!("assets/day_3_sample.txt"), part_1),
-            357
+            BigUint::from(357)
         );
     }
 
@@ -74,7 +212,7 @@ mod tests {
     fn test_day_3_part_1_real() {
         assert_eq!(
             total_output_joltage(include_str!("assets/day_3.txt"), part_1),
-            17155
+            BigUint::from(17155)
         );
     }
 
@@ -82,7 +220,7 @@ mod tests {
     fn test_day_3_part_2_sample() {
         assert_eq!(
             total_output_joltage(include_str!("assets/day_3_sample.txt"), part_2),
-            3121910778619
+            BigUint::from(3121910778619)
         );
     }
 
@@ -92,9 +230,167 @@ mod tests {
 
         assert_eq!(
             total_output_joltage(include_str!("assets/day_3.txt"), part_2),
-            169685670469164
+            BigUint::from(169685670469164)
         );
 
         println!("time taken: {:?}", t.elapsed());
     }
+
+    #[test]
+    fn test_day_3_max_joltage_indices() {
+        let bank = [8, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 9];
+
+        let joltage = max_joltage(&bank, 2);
+        assert_eq!(joltage.value, BigUint::from(89));
+        assert_eq!(joltage.indices, vec![0, 14]);
+
+        let joltage = max_joltage(&[2, 3, 4, 2, 3, 4, 2, 3, 4, 2, 7, 8], 4);
+        assert_eq!(joltage.value, BigUint::from(4478));
+        assert_eq!(joltage.indices, vec![2, 5, 10, 11]);
+
+        let joltage = max_joltage(&[5, 5, 5], 3);
+        assert_eq!(joltage.value, BigUint::from(555));
+        assert_eq!(joltage.indices, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_day_3_max_joltage_beyond_u64() {
+        let bank = [
+            9, 1, 8, 1, 7, 1, 6, 1, 5, 1, 4, 1, 3, 1, 2, 1, 1, 1, 9, 9, 9, 9, 9, 9, 9,
+        ];
+
+        assert_eq!(
+            max_joltage(&bank, 24).value.to_string(),
+            "981716151413121119999999"
+        );
+    }
+
+    #[test]
+    fn test_day_3_total_output_joltage_beyond_u64() {
+        let banks = "99999999999999999999999\n99999999999999999999999\n";
+
+        assert_eq!(
+            total_output_joltage(banks, |bank| max_joltage(bank, 22).value).to_string(),
+            "19999999999999999999998"
+        );
+    }
+
+    /// Every distinct value from picking k digits of the bank, largest first
+    fn brute_force_joltages(bank: &[u32], k: usize) -> Vec<Vec<u32>> {
+        let mut values: Vec<Vec<u32>> = (0u32..1 << bank.len())
+            .filter(|mask| mask.count_ones() as usize == k)
+            .map(|mask| {
+                (0..bank.len())
+                    .filter(|i| mask & (1 << i) != 0)
+                    .map(|i| bank[i])
+                    .collect()
+            })
+            .collect();
+
+        values.sort_unstable_by(|a, b| b.cmp(a));
+        values.dedup();
+        values
+    }
+
+    #[test]
+    fn test_day_3_min_joltage() {
+        let bank = [9, 8, 7, 6, 5, 4, 3, 2, 1, 1, 1, 1, 1, 1, 1];
+        assert_eq!(
+            min_joltage(&bank, 2, false).unwrap().value,
+            BigUint::from(11)
+        );
+
+        let bank = [3, 0, 2, 0, 1];
+        let joltage = min_joltage(&bank, 3, true).unwrap();
+        assert_eq!(joltage.value, BigUint::from(1));
+        assert_eq!(joltage.indices, vec![1, 3, 4]);
+
+        let joltage = min_joltage(&bank, 3, false).unwrap();
+        assert_eq!(joltage.value, BigUint::from(201));
+        assert_eq!(joltage.indices, vec![2, 3, 4]);
+
+        assert!(min_joltage(&[0, 0, 1], 3, false).is_none());
+    }
+
+    #[test]
+    fn test_day_3_nth_largest_joltage() {
+        let bank = [1, 2, 1, 3];
+
+        let values: Vec<String> = (1..=5)
+            .map(|n| nth_largest_joltage(&bank, 2, n).unwrap().value.to_string())
+            .collect();
+        assert_eq!(values, vec!["23", "21", "13", "12", "11"]);
+
+        assert!(nth_largest_joltage(&bank, 2, 6).is_none());
+        assert!(nth_largest_joltage(&bank, 2, 0).is_none());
+    }
+
+    #[test]
+    fn test_day_3_selection_against_brute_force() {
+        let banks: [&[u32]; 4] = [
+            &[3, 0, 2, 0, 1, 3, 3],
+            &[9, 1, 9, 1, 9, 1, 9, 1],
+            &[1, 1, 1, 0, 0, 0, 1, 2],
+            &[5, 4, 3, 2, 1, 2, 3, 4, 5],
+        ];
+
+        for bank in banks {
+            for k in 1..=bank.len() {
+                let expected = brute_force_joltages(bank, k);
+
+                assert_eq!(
+                    max_joltage(bank, k).value,
+                    BigUint::from_digits(&expected[0])
+                );
+                assert_eq!(
+                    min_joltage(bank, k, true).unwrap().value,
+                    BigUint::from_digits(expected.last().unwrap())
+                );
+                assert_eq!(
+                    min_joltage(bank, k, false).map(|j| j.value),
+                    expected
+                        .iter()
+                        .rev()
+                        .find(|digits| digits[0] != 0)
+                        .map(|digits| BigUint::from_digits(digits))
+                );
+
+                for (n, digits) in expected.iter().enumerate() {
+                    let joltage = nth_largest_joltage(bank, k, n as u64 + 1).unwrap();
+                    let chosen: Vec<u32> = joltage.indices.iter().map(|&i| bank[i]).collect();
+                    assert_eq!(&chosen, digits);
+                }
+                assert!(nth_largest_joltage(bank, k, expected.len() as u64 + 1).is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn test_day_3_worst_case_sample() {
+        assert_eq!(
+            total_output_joltage(include_str!("assets/day_3_sample.txt"), |bank| {
+                min_joltage(bank, 2, false).unwrap().value
+            }),
+            BigUint::from(11 + 11 + 22 + 11)
+        );
+    }
+
+    #[test]
+    fn test_day_3_streaming() {
+        let reader = BufReader::with_capacity(4, include_str!("assets/day_3.txt").as_bytes());
+
+        assert_eq!(
+            total_output_joltage_streaming(reader, part_2).unwrap(),
+            BigUint::from(169685670469164)
+        );
+
+        let reader = "12\r\n\n  34  \n56".as_bytes();
+        assert_eq!(
+            total_output_joltage_streaming(reader, part_1).unwrap(),
+            BigUint::from(12 + 34 + 56)
+        );
+
+        let err = total_output_joltage_streaming("12\n3x\n".as_bytes(), part_1).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
 }
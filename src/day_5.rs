@@ -1,18 +1,28 @@
-use std::cmp::Ordering::{self, Equal, Greater};
+use std::{
+    cmp::Ordering::{self, Equal, Greater},
+    io::{self, Read, Write},
+    num::ParseIntError,
+    str::FromStr,
+};
 
-#[derive(Eq, PartialEq)]
+/// Marks the start of a saved range database
+const MAGIC: &[u8; 4] = b"AOC5";
+const FORMAT_VERSION: u8 = 1;
+/// Magic, version, merge mode and range count
+const HEADER_LEN: usize = 4 + 1 + 1 + 8;
+const CHECKSUM_LEN: usize = 8;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 struct Range {
     min: u64,
     max: u64,
 }
 
 impl Range {
-    fn from_str(line: &str) -> Self {
-        let (min, max) = line.split_once("-").unwrap();
-        Self {
-            min: min.parse().unwrap(),
-            max: max.parse().unwrap(),
-        }
+    fn new(min: u64, max: u64) -> Self {
+        assert!(min <= max, "range {min}-{max} is backwards");
+
+        Self { min, max }
     }
 
     /// Used in binary search, if the value we're searching for is possibly within
@@ -22,9 +32,27 @@ impl Range {
         if value >= self.min { Equal } else { Greater }
     }
 
-    /// The number of ingredients in the range, inclusive
-    fn count(&self) -> u64 {
-        self.max - self.min + 1
+    /// The number of ingredients in the range, inclusive. u128 as 0-u64::MAX holds one more
+    /// than u64 can count
+    fn count(&self) -> u128 {
+        (self.max - self.min) as u128 + 1
+    }
+}
+
+impl FromStr for Range {
+    type Err = ParseErrorKind;
+
+    /// Parses `min-max`, allowing spaces around either number
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (min, max) = line.split_once('-').ok_or(ParseErrorKind::MissingDash)?;
+        let min = min.trim().parse().map_err(ParseErrorKind::InvalidNumber)?;
+        let max = max.trim().parse().map_err(ParseErrorKind::InvalidNumber)?;
+
+        if min > max {
+            return Err(ParseErrorKind::BackwardsRange { min, max });
+        }
+
+        Ok(Self { min, max })
     }
 }
 
@@ -42,20 +70,98 @@ impl PartialOrd for Range {
     }
 }
 
+/// Which ranges get joined together when merging
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+enum Merge {
+    /// Only ranges that share a value, so 3-5 and 6-8 stay apart
+    #[default]
+    Overlapping,
+    /// Ranges that share a value or sit right next to each other, so 3-5 and 6-8 become 3-8
+    Adjacent,
+}
+
+impl Merge {
+    /// Whether a range starting at `min` stays separate from one ending at `max`
+    fn separates(self, max: u64, min: u64) -> bool {
+        match self {
+            Merge::Overlapping => min > max,
+            Merge::Adjacent => min > max.saturating_add(1),
+        }
+    }
+}
+
+/// A range as it was written in the input, before any merging
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Source {
+    line: usize,
+    range: Range,
+}
+
+/// A set of ingredient IDs, kept as ranges sorted by min that never overlap
+#[derive(Clone, Debug)]
 struct Ranges {
     ranges: Vec<Range>,
+    /// The original ranges the set was built from, sorted by min. insert and remove leave
+    /// these as they are, so inserted ranges have no source. Sets made by combining other sets
+    /// or loaded by read_from don't have any.
+    sources: Vec<Source>,
+    merge: Merge,
+}
+
+/// Sets are equal when they hold the same IDs, however they were made and whichever merge
+/// mode they use
+impl PartialEq for Ranges {
+    fn eq(&self, other: &Self) -> bool {
+        self.coalesced().eq(other.coalesced())
+    }
 }
 
+impl Eq for Ranges {}
+
 impl Ranges {
-    fn new(mut ranges: Vec<Range>) -> Self {
-        ranges.sort_unstable();
+    /// Builds the set from ranges in input order, so the first is from line 1 and so on
+    fn new(ranges: Vec<Range>) -> Self {
+        Self::with_merge(ranges, Merge::default())
+    }
+
+    fn with_merge(ranges: Vec<Range>, merge: Merge) -> Self {
+        Self::from_sources(
+            ranges
+                .into_iter()
+                .enumerate()
+                .map(|(i, range)| Source { line: i + 1, range })
+                .collect(),
+            merge,
+        )
+    }
+
+    fn from_sources(mut sources: Vec<Source>, merge: Merge) -> Self {
+        sources.sort_unstable_by_key(|source| source.range);
+
+        Self {
+            ranges: Self::merge(sources.iter().map(|source| source.range), merge),
+            sources,
+            merge,
+        }
+    }
+
+    /// A set without any sources, from ranges that are already sorted and don't overlap
+    fn from_merged(ranges: Vec<Range>, merge: Merge) -> Self {
+        Self {
+            ranges: Self::merge(ranges.into_iter(), merge),
+            sources: Vec::new(),
+            merge,
+        }
+    }
 
+    /// Merges ranges, which must be sorted by min
+    fn merge(ranges: impl Iterator<Item = Range>, merge: Merge) -> Vec<Range> {
         let mut merged_ranges: Vec<Range> = Vec::new();
 
         for r in ranges {
             match merged_ranges.last_mut() {
                 Some(Range { max: last_max, .. }) => {
-                    if r.min > *last_max {
+                    if merge.separates(*last_max, r.min) {
                         merged_ranges.push(r);
                     } else if r.max > *last_max {
                         *last_max = r.max;
@@ -67,9 +173,7 @@ impl Ranges {
             }
         }
 
-        Self {
-            ranges: merged_ranges,
-        }
+        merged_ranges
     }
 
     /// Binary search to efficiently see if any of the ranges contain value
@@ -89,36 +193,460 @@ impl Ranges {
         values.iter().filter(|&&val| self.contains(val)).count()
     }
 
+    /// For each value, the range containing it if any, in the same order as values.
+    ///
+    /// Rather than a binary search per value, the values are sorted and swept through the
+    /// ranges in a single pass. The sort costs more than the binary searches it saves on
+    /// shuffled values, so this wins when there are lots of values that are already sorted.
+    fn find_containing(&self, values: &[u64]) -> Vec<Option<&Range>> {
+        let mut order: Vec<usize> = (0..values.len()).collect();
+        order.sort_unstable_by_key(|&i| values[i]);
+
+        let mut result = vec![None; values.len()];
+        let mut ranges = self.ranges.iter().peekable();
+
+        for i in order {
+            let value = values[i];
+
+            // values only go up, so ranges that end before this one are done with
+            while ranges.next_if(|range| range.max < value).is_some() {}
+
+            match ranges.peek() {
+                Some(range) if range.min <= value => result[i] = Some(*range),
+                Some(_) => {}
+                None => break,
+            }
+        }
+
+        result
+    }
+
+    /// Same as count_containing, but using the sorted sweep from find_containing
+    fn count_containing_sweep(&self, values: &[u64]) -> usize {
+        self.find_containing(values).iter().flatten().count()
+    }
+
+    /// Every original range containing value, with the line it came from, so it's possible
+    /// to tell which lines made an ingredient fresh
+    fn sources_containing(&self, value: u64) -> Vec<&Source> {
+        if !self.contains(value) {
+            return Vec::new();
+        }
+
+        let candidates = self
+            .sources
+            .partition_point(|source| source.range.min <= value);
+
+        self.sources[..candidates]
+            .iter()
+            .filter(|source| value <= source.range.max)
+            .collect()
+    }
+
     /// returns the total count of all ranges
-    fn count(&self) -> u64 {
+    fn count(&self) -> u128 {
         self.ranges.iter().map(|range| range.count()).sum()
     }
+
+    /// The ranges with touching ones joined, which is the same for every merge mode
+    fn coalesced(&self) -> impl Iterator<Item = Range> + '_ {
+        let mut ranges = self.ranges.iter().copied().peekable();
+
+        std::iter::from_fn(move || {
+            let mut range = ranges.next()?;
+            while let Some(next) = ranges.next_if(|next| range.max.checked_add(1) == Some(next.min))
+            {
+                range.max = next.max;
+            }
+            Some(range)
+        })
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Range> {
+        self.ranges.iter()
+    }
+
+    /// The indices of the ranges that `merge` would join with `range`, as ranges are ordered
+    /// by both min and max they're always contiguous
+    fn joinable(&self, range: &Range, merge: Merge) -> std::ops::Range<usize> {
+        let start = self
+            .ranges
+            .partition_point(|r| merge.separates(r.max, range.min));
+        let end = self
+            .ranges
+            .partition_point(|r| !merge.separates(range.max, r.min));
+
+        start..end
+    }
+
+    /// The indices of the ranges that share at least one value with `range`
+    fn overlapping(&self, range: &Range) -> std::ops::Range<usize> {
+        self.joinable(range, Merge::Overlapping)
+    }
+
+    /// Adds every value in `range`, merging it with any ranges it overlaps (or touches, if
+    /// merging adjacent ranges)
+    fn insert(&mut self, range: Range) {
+        let overlapping = self.joinable(&range, self.merge);
+
+        let merged = if overlapping.is_empty() {
+            range
+        } else {
+            Range {
+                min: range.min.min(self.ranges[overlapping.start].min),
+                max: range.max.max(self.ranges[overlapping.end - 1].max),
+            }
+        };
+
+        self.ranges.splice(overlapping, [merged]);
+    }
+
+    /// Takes out every value in `range`, trimming or splitting any ranges it overlaps
+    fn remove(&mut self, range: &Range) {
+        let overlapping = self.overlapping(range);
+        let mut remaining = Vec::with_capacity(2);
+
+        if !overlapping.is_empty() {
+            let first = self.ranges[overlapping.start];
+            let last = self.ranges[overlapping.end - 1];
+
+            if first.min < range.min {
+                remaining.push(Range::new(first.min, range.min - 1));
+            }
+            if last.max > range.max {
+                remaining.push(Range::new(range.max + 1, last.max));
+            }
+        }
+
+        self.ranges.splice(overlapping, remaining);
+    }
+
+    /// Values in either set
+    fn union(&self, other: &Ranges) -> Ranges {
+        let mut ranges: Vec<Range> = self.iter().chain(other.iter()).copied().collect();
+        ranges.sort_unstable();
+
+        Self::from_merged(ranges, self.merge)
+    }
+
+    /// Values in both sets, found by walking the two sorted lists together
+    fn intersection(&self, other: &Ranges) -> Ranges {
+        let mut ranges = Vec::new();
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            let min = x.min.max(y.min);
+            let max = x.max.min(y.max);
+
+            if min <= max {
+                ranges.push(Range { min, max });
+            }
+
+            // whichever finishes first can't overlap anything further on in the other
+            if x.max < y.max {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+
+        Self::from_merged(ranges, self.merge)
+    }
+
+    /// Values in this set but not in other
+    fn difference(&self, other: &Ranges) -> Ranges {
+        self.intersection(&other.complement(Range::new(0, u64::MAX)))
+    }
+
+    /// Values within `bounds` that aren't in this set
+    fn complement(&self, bounds: Range) -> Ranges {
+        let mut ranges = Vec::new();
+        // the lowest value that isn't known to be covered yet
+        let mut from = bounds.min;
+
+        for r in &self.ranges[self.overlapping(&bounds)] {
+            if r.min > from {
+                ranges.push(Range::new(from, r.min - 1));
+            }
+
+            match r.max.checked_add(1) {
+                Some(next) if next <= bounds.max => from = next,
+                _ => return Self::from_merged(ranges, self.merge),
+            }
+        }
+
+        ranges.push(Range::new(from, bounds.max));
+
+        Self::from_merged(ranges, self.merge)
+    }
+
+    /// The runs of values between consecutive ranges
+    fn gaps(&self) -> impl Iterator<Item = Range> {
+        self.ranges
+            .windows(2)
+            .filter(|pair| pair[1].min > pair[0].max + 1)
+            .map(|pair| Range::new(pair[0].max + 1, pair[1].min - 1))
+    }
+
+    /// Saves the merged ranges (not the sources) so they can be loaded again without
+    /// merging. The layout, all little endian, is the magic bytes, a format version, the
+    /// merge mode, the range count, min and max for each range, then an FNV-1a checksum of
+    /// everything before it.
+    fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.ranges.len() * 16 + CHECKSUM_LEN);
+
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(FORMAT_VERSION);
+        bytes.push(match self.merge {
+            Merge::Overlapping => 0,
+            Merge::Adjacent => 1,
+        });
+        bytes.extend_from_slice(&(self.ranges.len() as u64).to_le_bytes());
+        for range in &self.ranges {
+            bytes.extend_from_slice(&range.min.to_le_bytes());
+            bytes.extend_from_slice(&range.max.to_le_bytes());
+        }
+        bytes.extend_from_slice(&fnv1a(&bytes).to_le_bytes());
+
+        writer.write_all(&bytes)
+    }
+
+    /// Loads ranges saved by write_to. They're only checked to still be sorted and merged,
+    /// never re-sorted.
+    fn read_from(mut reader: impl Read) -> Result<Self, LoadRangesError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        if bytes.len() < 5 || &bytes[0..4] != MAGIC {
+            return Err(LoadRangesError::NotADatabase);
+        }
+
+        // a future version may lay out the rest, checksum included, differently
+        if bytes[4] != FORMAT_VERSION {
+            return Err(LoadRangesError::UnsupportedVersion(bytes[4]));
+        }
+
+        if bytes.len() < HEADER_LEN + CHECKSUM_LEN {
+            return Err(LoadRangesError::NotADatabase);
+        }
+
+        let (body, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+        if fnv1a(body).to_le_bytes() != checksum {
+            return Err(LoadRangesError::ChecksumMismatch);
+        }
+
+        let merge = match body[5] {
+            0 => Merge::Overlapping,
+            1 => Merge::Adjacent,
+            other => return Err(LoadRangesError::UnknownMerge(other)),
+        };
+
+        let count = u64::from_le_bytes(body[6..HEADER_LEN].try_into().unwrap());
+        let range_bytes = &body[HEADER_LEN..];
+        if range_bytes.len() as u64 != count.saturating_mul(16) {
+            return Err(LoadRangesError::WrongLength);
+        }
+
+        let ranges: Vec<Range> = range_bytes
+            .chunks_exact(16)
+            .map(|chunk| Range {
+                min: u64::from_le_bytes(chunk[0..8].try_into().unwrap()),
+                max: u64::from_le_bytes(chunk[8..16].try_into().unwrap()),
+            })
+            .collect();
+
+        let merged = ranges.iter().all(|range| range.min <= range.max)
+            && ranges
+                .windows(2)
+                .all(|pair| merge.separates(pair[0].max, pair[1].min));
+        if !merged {
+            return Err(LoadRangesError::NotMerged);
+        }
+
+        Ok(Self {
+            ranges,
+            sources: Vec::new(),
+            merge,
+        })
+    }
+}
+
+/// The puzzle input: the fresh ingredient ranges, then the available ingredient IDs
+struct Inventory {
+    ranges: Ranges,
+    ingredients: Vec<u64>,
+}
+
+impl Inventory {
+    /// Parses the two sections, which are split by the first blank (or whitespace only) line
+    /// after the ranges start. Windows line endings and trailing whitespace are fine, and
+    /// anything after a `#` is a comment, so comment-only lines are skipped.
+    fn parse(s: &str) -> Result<Self, ParseError> {
+        let mut sources = Vec::new();
+        let mut ingredients = Vec::new();
+        let mut in_ranges = true;
+
+        for (i, raw) in s.lines().enumerate() {
+            let line_number = i + 1;
+            let (line, comment) = match raw.split_once('#') {
+                Some((line, _)) => (line.trim(), true),
+                None => (raw.trim(), false),
+            };
+
+            if line.is_empty() {
+                if in_ranges && !comment && !sources.is_empty() {
+                    in_ranges = false;
+                }
+                continue;
+            }
+
+            let with_line = |kind| ParseError {
+                line: line_number,
+                kind,
+            };
+
+            if in_ranges {
+                let range = line.parse().map_err(with_line)?;
+                sources.push(Source {
+                    line: line_number,
+                    range,
+                });
+            } else {
+                let id = line
+                    .parse()
+                    .map_err(|e| with_line(ParseErrorKind::InvalidNumber(e)))?;
+                ingredients.push(id);
+            }
+        }
+
+        Ok(Self {
+            ranges: Ranges::from_sources(sources, Merge::default()),
+            ingredients,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct ParseError {
+    /// 1 based line number in the input
+    line: usize,
+    kind: ParseErrorKind,
+}
+
+#[derive(Debug, PartialEq)]
+enum ParseErrorKind {
+    MissingDash,
+    InvalidNumber(ParseIntError),
+    BackwardsRange { min: u64, max: u64 },
+}
+
+#[derive(Debug)]
+enum LoadRangesError {
+    Io(io::Error),
+    NotADatabase,
+    UnsupportedVersion(u8),
+    UnknownMerge(u8),
+    ChecksumMismatch,
+    WrongLength,
+    NotMerged,
+}
+
+impl From<io::Error> for LoadRangesError {
+    fn from(e: io::Error) -> Self {
+        LoadRangesError::Io(e)
+    }
+}
+
+/// 64 bit FNV-1a, plenty to catch a truncated or corrupted file
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
 }
 
 #[cfg(test)]
 mod tests {
-    use std::str::Lines;
-
-    use crate::bench;
+    use crate::{Rng, bench};
 
     use super::*;
 
-    fn parse_ranges(s: &str) -> (Ranges, Lines<'_>) {
-        let mut lines = s.lines();
-        let range_lines: Vec<Range> = lines
-            .by_ref()
-            .take_while(|l| !l.is_empty())
-            .map(Range::from_str)
-            .collect();
+    fn parse_input(s: &str) -> (Ranges, Vec<u64>) {
+        let inventory = Inventory::parse(s).unwrap();
 
-        (Ranges::new(range_lines), lines)
+        (inventory.ranges, inventory.ingredients)
     }
 
-    fn parse_input(s: &str) -> (Ranges, Vec<u64>) {
-        let (ranges, lines) = parse_ranges(s);
-        let ingredient_lines = lines.map(|l| l.parse().unwrap()).collect();
+    fn ranges(pairs: &[(u64, u64)]) -> Ranges {
+        Ranges::new(
+            pairs
+                .iter()
+                .map(|&(min, max)| Range::new(min, max))
+                .collect(),
+        )
+    }
+
+    fn pairs(ranges: &Ranges) -> Vec<(u64, u64)> {
+        ranges.iter().map(|r| (r.min, r.max)).collect()
+    }
+
+    #[test]
+    fn test_day_5_insert() {
+        let mut set = ranges(&[(3, 5), (10, 14), (20, 25)]);
+
+        set.insert(Range::new(7, 8));
+        assert_eq!(pairs(&set), vec![(3, 5), (7, 8), (10, 14), (20, 25)]);
+
+        set.insert(Range::new(12, 21));
+        assert_eq!(pairs(&set), vec![(3, 5), (7, 8), (10, 25)]);
+
+        set.insert(Range::new(0, 100));
+        assert_eq!(pairs(&set), vec![(0, 100)]);
+    }
+
+    #[test]
+    fn test_day_5_remove() {
+        let mut set = ranges(&[(3, 5), (10, 14), (20, 25)]);
+
+        set.remove(&Range::new(12, 12));
+        assert_eq!(pairs(&set), vec![(3, 5), (10, 11), (13, 14), (20, 25)]);
+
+        set.remove(&Range::new(4, 21));
+        assert_eq!(pairs(&set), vec![(3, 3), (22, 25)]);
 
-        (ranges, ingredient_lines)
+        set.remove(&Range::new(0, u64::MAX));
+        assert_eq!(pairs(&set), vec![]);
+    }
+
+    #[test]
+    fn test_day_5_set_operations() {
+        let a = ranges(&[(0, 5), (10, 20), (30, 40)]);
+        let b = ranges(&[(3, 12), (18, 32), (50, 60)]);
+
+        assert_eq!(pairs(&a.union(&b)), vec![(0, 40), (50, 60)]);
+        assert_eq!(
+            pairs(&a.intersection(&b)),
+            vec![(3, 5), (10, 12), (18, 20), (30, 32)]
+        );
+        assert_eq!(pairs(&a.difference(&b)), vec![(0, 2), (13, 17), (33, 40)]);
+        assert_eq!(
+            pairs(&a.complement(Range::new(2, 35))),
+            vec![(6, 9), (21, 29)]
+        );
+        assert_eq!(
+            pairs(&a.complement(Range::new(0, u64::MAX))),
+            vec![(6, 9), (21, 29), (41, u64::MAX)]
+        );
+        assert_eq!(
+            pairs(&ranges(&[(0, u64::MAX)]).complement(Range::new(0, u64::MAX))),
+            vec![]
+        );
+        assert_eq!(
+            a.gaps().map(|r| (r.min, r.max)).collect::<Vec<_>>(),
+            vec![(6, 9), (21, 29)]
+        );
     }
 
     #[test]
@@ -137,9 +665,380 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_day_5_find_containing() {
+        let (ranges, _) = parse_input(include_str!("assets/day_5_sample.txt"));
+
+        let found: Vec<Option<(u64, u64)>> = ranges
+            .find_containing(&[17, 1, 5, 32, 8, 11, 3, 10, 20])
+            .into_iter()
+            .map(|r| r.map(|r| (r.min, r.max)))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                Some((10, 20)),
+                None,
+                Some((3, 5)),
+                None,
+                None,
+                Some((10, 20)),
+                Some((3, 5)),
+                Some((10, 20)),
+                Some((10, 20))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_day_5_part_1_real_sweep() {
+        let (ranges, ingredients) = parse_input(include_str!("assets/day_5.txt"));
+
+        for (value, found) in ingredients.iter().zip(ranges.find_containing(&ingredients)) {
+            assert_eq!(ranges.contains(*value), found.is_some());
+            assert!(found.is_none_or(|r| r.min <= *value && *value <= r.max));
+        }
+
+        assert_eq!(ranges.count_containing_sweep(&ingredients), 638);
+
+        // The real ingredients, then lots of sorted ones, where the sweep's sort is cheap and
+        // it beats a binary search per value
+        let top = ranges.iter().last().unwrap().max;
+        let mut rng = Rng(0x2025_0036);
+        let mut many: Vec<u64> = (0..100_000).map(|_| rng.below(top + 1)).collect();
+        many.sort_unstable();
+
+        for (values, runs) in [(&ingredients, 1000), (&many, 20)] {
+            let expected = ranges.count_containing(values);
+            assert_eq!(ranges.count_containing_sweep(values), expected);
+
+            println!("{} values, binary search:", values.len());
+            bench(runs, || {
+                assert_eq!(ranges.count_containing(values), expected)
+            });
+            println!("{} values, sweep:", values.len());
+            bench(runs, || {
+                assert_eq!(ranges.count_containing_sweep(values), expected)
+            });
+        }
+    }
+
+    #[test]
+    fn test_day_5_sources_containing() {
+        let (mut ranges, _) = parse_input(include_str!("assets/day_5_sample.txt"));
+
+        let lines = |ranges: &Ranges, value| -> Vec<usize> {
+            ranges
+                .sources_containing(value)
+                .iter()
+                .map(|source| source.line)
+                .collect()
+        };
+
+        assert_eq!(lines(&ranges, 1), vec![]);
+        assert_eq!(lines(&ranges, 5), vec![1]);
+        assert_eq!(lines(&ranges, 11), vec![2]);
+        assert_eq!(lines(&ranges, 13), vec![2, 4]);
+        assert_eq!(lines(&ranges, 17), vec![4, 3]);
+        assert_eq!(lines(&ranges, 20), vec![3]);
+
+        assert_eq!(
+            ranges.sources_containing(17)[0].range,
+            "12-18".parse().unwrap()
+        );
+
+        ranges.remove(&Range::new(17, 17));
+        assert_eq!(lines(&ranges, 17), vec![]);
+        assert_eq!(lines(&ranges, 18), vec![4, 3]);
+    }
+
+    #[test]
+    fn test_day_5_merge_adjacent() {
+        let pieces = || vec![Range::new(3, 5), Range::new(6, 8), Range::new(10, 12)];
+
+        let overlapping = Ranges::new(pieces());
+        assert_eq!(pairs(&overlapping), vec![(3, 5), (6, 8), (10, 12)]);
+
+        let mut adjacent = Ranges::with_merge(pieces(), Merge::Adjacent);
+        assert_eq!(pairs(&adjacent), vec![(3, 8), (10, 12)]);
+
+        adjacent.insert(Range::new(9, 9));
+        assert_eq!(pairs(&adjacent), vec![(3, 12)]);
+
+        let edges = vec![Range::new(0, 0), Range::new(1, u64::MAX - 1)];
+        let mut edges = Ranges::with_merge(edges, Merge::Adjacent);
+        edges.insert(Range::new(u64::MAX, u64::MAX));
+        assert_eq!(pairs(&edges), vec![(0, u64::MAX)]);
+    }
+
+    #[test]
+    fn test_day_5_count_full_domain() {
+        let ranges = Ranges::new(vec![Range::new(0, u64::MAX)]);
+
+        assert_eq!(ranges.count(), u64::MAX as u128 + 1);
+    }
+
+    /// The values (all below 64) in a range, as a bit set
+    fn range_bits(range: &Range) -> u64 {
+        (range.min..=range.max).fold(0, |bits, value| bits | 1 << value)
+    }
+
+    fn random_range(rng: &mut Rng) -> Range {
+        let min = rng.below(64);
+        Range::new(min, min + rng.below(64 - min))
+    }
+
+    /// Checks the ranges are sorted, separate (by the set's merge rule) and hold exactly
+    /// the values in `bits`
+    fn assert_matches(ranges: &Ranges, bits: u64) {
+        for pair in ranges.ranges.windows(2) {
+            assert!(ranges.merge.separates(pair[0].max, pair[1].min));
+        }
+
+        assert_eq!(ranges.count(), bits.count_ones() as u128);
+        for value in 0..64 {
+            assert_eq!(ranges.contains(value), bits & 1 << value != 0);
+        }
+    }
+
+    #[test]
+    fn test_day_5_against_brute_force() {
+        let mut rng = Rng(0x2025_0005);
+
+        for merge in [Merge::Overlapping, Merge::Adjacent] {
+            for _ in 0..500 {
+                let a_ranges: Vec<Range> =
+                    (0..rng.below(6)).map(|_| random_range(&mut rng)).collect();
+                let b_ranges: Vec<Range> =
+                    (0..rng.below(6)).map(|_| random_range(&mut rng)).collect();
+                let a_bits = a_ranges.iter().fold(0, |bits, r| bits | range_bits(r));
+                let b_bits = b_ranges.iter().fold(0, |bits, r| bits | range_bits(r));
+
+                let a = Ranges::with_merge(a_ranges.clone(), merge);
+                let b = Ranges::with_merge(b_ranges, merge);
+                assert_matches(&a, a_bits);
+                assert_matches(&b, b_bits);
+
+                assert_matches(&a.union(&b), a_bits | b_bits);
+                assert_matches(&a.intersection(&b), a_bits & b_bits);
+                assert_matches(&a.difference(&b), a_bits & !b_bits);
+
+                let bounds = random_range(&mut rng);
+                assert_matches(&a.complement(bounds), range_bits(&bounds) & !a_bits);
+
+                let gap_bits = a.gaps().fold(0, |bits, r| bits | range_bits(&r));
+                let hull = a.ranges.first().map_or(0, |first| {
+                    range_bits(&Range::new(first.min, a.ranges.last().unwrap().max))
+                });
+                assert_eq!(gap_bits, hull & !a_bits);
+
+                let (inserted, removed) = (random_range(&mut rng), random_range(&mut rng));
+                let mut edited = a.clone();
+                edited.insert(inserted);
+                assert_matches(&edited, a_bits | range_bits(&inserted));
+                edited.remove(&removed);
+                assert_matches(
+                    &edited,
+                    (a_bits | range_bits(&inserted)) & !range_bits(&removed),
+                );
+
+                let values: Vec<u64> = (0..64).collect();
+                for (value, found) in values.iter().zip(a.find_containing(&values)) {
+                    assert_eq!(found.is_some(), a_bits & 1 << value != 0);
+                    assert!(found.is_none_or(|r| r.min <= *value && *value <= r.max));
+
+                    let mut lines: Vec<usize> = a
+                        .sources_containing(*value)
+                        .iter()
+                        .map(|s| s.line)
+                        .collect();
+                    lines.sort_unstable();
+                    let expected: Vec<usize> = (1..=a_ranges.len())
+                        .filter(|&line| range_bits(&a_ranges[line - 1]) & 1 << value != 0)
+                        .collect();
+                    assert_eq!(lines, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_day_5_save_and_load() {
+        let (ranges, ingredients) = parse_input(include_str!("assets/day_5.txt"));
+
+        let mut saved = Vec::new();
+        ranges.write_to(&mut saved).unwrap();
+        assert_eq!(
+            saved.len(),
+            HEADER_LEN + ranges.ranges.len() * 16 + CHECKSUM_LEN
+        );
+
+        let loaded = Ranges::read_from(saved.as_slice()).unwrap();
+        assert_eq!(loaded.ranges, ranges.ranges);
+        assert_eq!(loaded.merge, ranges.merge);
+        assert_eq!(loaded.count_containing(&ingredients), 638);
+        assert_eq!(loaded.count(), 352946349407338);
+
+        let adjacent =
+            Ranges::with_merge(vec![Range::new(3, 5), Range::new(6, 8)], Merge::Adjacent);
+        let mut saved = Vec::new();
+        adjacent.write_to(&mut saved).unwrap();
+        let loaded = Ranges::read_from(saved.as_slice()).unwrap();
+        assert_eq!(loaded.merge, Merge::Adjacent);
+        assert_eq!(pairs(&loaded), vec![(3, 8)]);
+    }
+
+    #[test]
+    fn test_day_5_equality_ignores_history() {
+        let (ranges, _) = parse_input(include_str!("assets/day_5.txt"));
+
+        let mut saved = Vec::new();
+        ranges.write_to(&mut saved).unwrap();
+        assert_eq!(Ranges::read_from(saved.as_slice()).unwrap(), ranges);
+        assert_eq!(ranges.union(&ranges), ranges);
+
+        let overlapping = Ranges::new(vec![Range::new(3, 5), Range::new(6, 8)]);
+        let adjacent =
+            Ranges::with_merge(vec![Range::new(6, 8), Range::new(3, 5)], Merge::Adjacent);
+        assert_eq!(overlapping, adjacent);
+
+        let mut inserted = Ranges::new(vec![Range::new(3, 4)]);
+        inserted.insert(Range::new(5, 8));
+        assert_eq!(inserted, adjacent);
+
+        inserted.remove(&Range::new(8, 8));
+        assert_ne!(inserted, adjacent);
+    }
+
+    #[test]
+    fn test_day_5_load_rejects_bad_data() {
+        let (ranges, _) = parse_input(include_str!("assets/day_5_sample.txt"));
+        let mut saved = Vec::new();
+        ranges.write_to(&mut saved).unwrap();
+
+        let load = |bytes: &[u8]| Ranges::read_from(bytes).unwrap_err();
+
+        assert!(matches!(load(b"nope"), LoadRangesError::NotADatabase));
+        assert!(matches!(
+            load(&saved[..saved.len() - 1]),
+            LoadRangesError::ChecksumMismatch
+        ));
+
+        let mut corrupted = saved.clone();
+        corrupted[HEADER_LEN] ^= 1;
+        assert!(matches!(
+            load(&corrupted),
+            LoadRangesError::ChecksumMismatch
+        ));
+
+        // re-checksum after tampering, so only the later checks can catch it
+        let rechecksum = |mut bytes: Vec<u8>| {
+            bytes.truncate(bytes.len() - CHECKSUM_LEN);
+            let checksum = fnv1a(&bytes);
+            bytes.extend_from_slice(&checksum.to_le_bytes());
+            bytes
+        };
+
+        let mut future = saved.clone();
+        future[4] = FORMAT_VERSION + 1;
+        assert!(matches!(
+            load(&rechecksum(future)),
+            LoadRangesError::UnsupportedVersion(2)
+        ));
+
+        // a future version with its own trailer is still recognised as a future version
+        let mut future = saved[..HEADER_LEN].to_vec();
+        future[4] = FORMAT_VERSION + 1;
+        future.extend_from_slice(b"new trailer");
+        assert!(matches!(
+            load(&future),
+            LoadRangesError::UnsupportedVersion(2)
+        ));
+        assert!(matches!(
+            load(b"AOC5\x02"),
+            LoadRangesError::UnsupportedVersion(2)
+        ));
+
+        let mut unknown_merge = saved.clone();
+        unknown_merge[5] = 7;
+        assert!(matches!(
+            load(&rechecksum(unknown_merge)),
+            LoadRangesError::UnknownMerge(7)
+        ));
+
+        let mut short = saved.clone();
+        short[6] += 1;
+        assert!(matches!(
+            load(&rechecksum(short)),
+            LoadRangesError::WrongLength
+        ));
+
+        // swap the first two ranges so they're out of order
+        let mut unsorted = saved.clone();
+        let (first, second) = unsorted[HEADER_LEN..HEADER_LEN + 32].split_at_mut(16);
+        first.swap_with_slice(second);
+        assert!(matches!(
+            load(&rechecksum(unsorted)),
+            LoadRangesError::NotMerged
+        ));
+    }
+
+    #[test]
+    fn test_day_5_parse_tolerates_formatting() {
+        let input = "# fresh ranges\r\n3-5  \r\n 10 - 14\r\n# comment only\r\n16-20 # late\r\n12-18\r\n  \t \r\n1\r\n# ids\r\n5  \r\n\r\n8\r\n";
+
+        let inventory = Inventory::parse(input).unwrap();
+        assert_eq!(pairs(&inventory.ranges), vec![(3, 5), (10, 20)]);
+        assert_eq!(inventory.ingredients, vec![1, 5, 8]);
+        assert_eq!(
+            inventory
+                .ranges
+                .sources_containing(17)
+                .iter()
+                .map(|s| s.line)
+                .collect::<Vec<_>>(),
+            vec![6, 5]
+        );
+    }
+
+    #[test]
+    fn test_day_5_parse_errors() {
+        let error = |input| Inventory::parse(input).err().unwrap();
+
+        assert_eq!(
+            error("3-5\n10\n"),
+            ParseError {
+                line: 2,
+                kind: ParseErrorKind::MissingDash
+            }
+        );
+        assert_eq!(
+            error("3-5\n14-10\n"),
+            ParseError {
+                line: 2,
+                kind: ParseErrorKind::BackwardsRange { min: 14, max: 10 }
+            }
+        );
+        assert!(matches!(
+            error("3-x\n"),
+            ParseError {
+                line: 1,
+                kind: ParseErrorKind::InvalidNumber(_)
+            }
+        ));
+        assert!(matches!(
+            error("3-5\n\n1\n-2\n"),
+            ParseError {
+                line: 4,
+                kind: ParseErrorKind::InvalidNumber(_)
+            }
+        ));
+    }
+
     #[test]
     fn test_day_5_part_2_sample() {
-        let (ranges, _) = parse_ranges(include_str!("assets/day_5_sample.txt"));
+        let (ranges, _) = parse_input(include_str!("assets/day_5_sample.txt"));
 
         assert_eq!(ranges.count(), 14);
     }
@@ -147,7 +1046,7 @@ mod tests {
     #[test]
     fn test_day_5_part_2_real() {
         bench(1000, || {
-            let (ranges, _) = parse_ranges(include_str!("assets/day_5.txt"));
+            let (ranges, _) = parse_input(include_str!("assets/day_5.txt"));
 
             assert_eq!(ranges.count(), 352946349407338);
         });
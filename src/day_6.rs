@@ -1,100 +1,720 @@
-fn part_1(s: &str) -> u64 {
-    let mut lines = s.lines().rev();
-    let operators: Vec<&str> = lines.next().unwrap().split_whitespace().collect();
-    let row_iters = lines.map(|l| l.split_whitespace().map(|o| o.parse::<u64>().unwrap()));
-    let mut results = vec![0; operators.len()];
-
-    for row_iter in row_iters {
-        for (col, value) in row_iter.enumerate() {
-            match operators[col] {
-                "+" => {
-                    results[col] += value;
-                }
-                "*" => {
-                    if results[col] == 0 {
-                        results[col] = 1;
-                    }
-                    results[col] *= value;
+use std::{
+    fmt,
+    io::{self, BufRead},
+    ops::Range,
+};
+
+use crate::big_uint::BigUint;
+
+/// The operators a problem can use. All of them work left to right through the operands,
+/// so `-` on 10, 3, 2 is (10 - 3) - 2.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Op {
+    /// `+`
+    Add,
+    /// `*`
+    Mul,
+    /// `-`
+    Sub,
+    /// `/`, integer division rounding down
+    Div,
+    /// `<`, the smallest operand
+    Min,
+    /// `>`, the largest operand
+    Max,
+    /// `^`
+    Pow,
+}
+
+impl TryFrom<char> for Op {
+    type Error = char;
+
+    fn try_from(op: char) -> Result<Self, Self::Error> {
+        match op {
+            '+' => Ok(Op::Add),
+            '*' => Ok(Op::Mul),
+            '-' => Ok(Op::Sub),
+            '/' => Ok(Op::Div),
+            '<' => Ok(Op::Min),
+            '>' => Ok(Op::Max),
+            '^' => Ok(Op::Pow),
+            op => Err(op),
+        }
+    }
+}
+
+impl From<Op> for char {
+    fn from(op: Op) -> Self {
+        match op {
+            Op::Add => '+',
+            Op::Mul => '*',
+            Op::Sub => '-',
+            Op::Div => '/',
+            Op::Min => '<',
+            Op::Max => '>',
+            Op::Pow => '^',
+        }
+    }
+}
+
+impl Op {
+    /// The answer for a problem with no operands, if that makes sense for the op
+    fn identity<N: Number>(self) -> Option<N> {
+        match self {
+            Op::Add => Some(N::from_u64(0)),
+            Op::Mul => Some(N::from_u64(1)),
+            _ => None,
+        }
+    }
+
+    fn apply<N: Number>(self, acc: N, value: N) -> Result<N, EvalError> {
+        match self {
+            Op::Add => acc.try_add(value).ok_or(EvalError::Overflow),
+            Op::Mul => acc.try_mul(value).ok_or(EvalError::Overflow),
+            Op::Sub => acc.try_sub(value).ok_or(EvalError::Negative),
+            Op::Div => acc.try_div(value).ok_or(EvalError::DivisionByZero),
+            Op::Min => Ok(acc.min(value)),
+            Op::Max => Ok(acc.max(value)),
+            Op::Pow => acc.try_pow(value).ok_or(EvalError::Overflow),
+        }
+    }
+}
+
+/// The largest answer, in bits, a BigUint power can have, about 79,000 decimal digits
+const MAX_POW_BITS: u64 = 1 << 18;
+
+/// A type problems can be evaluated in, u64 for speed, u128 for headroom or BigUint when
+/// the answers need to be exact however large they get
+trait Number: Sized + Ord {
+    fn from_u64(value: u64) -> Self;
+    /// None if the value doesn't fit
+    fn from_big(value: &BigUint) -> Option<Self>;
+    /// None if the answer doesn't fit
+    fn try_add(self, other: Self) -> Option<Self>;
+    /// None if the answer doesn't fit
+    fn try_mul(self, other: Self) -> Option<Self>;
+    /// None if the answer would be negative
+    fn try_sub(self, other: Self) -> Option<Self>;
+    /// None when dividing by zero
+    fn try_div(self, other: Self) -> Option<Self>;
+    /// None if the answer doesn't fit, exponents are never allowed beyond u32 (other than
+    /// for 0 and 1, which don't grow)
+    fn try_pow(self, exp: Self) -> Option<Self>;
+}
+
+macro_rules! impl_number {
+    ($($t:ty),*) => {$(
+        impl Number for $t {
+            fn from_u64(value: u64) -> Self {
+                value as $t
+            }
+
+            fn from_big(value: &BigUint) -> Option<Self> {
+                value.to_u128().and_then(|value| <$t>::try_from(value).ok())
+            }
+
+            fn try_add(self, other: Self) -> Option<Self> {
+                self.checked_add(other)
+            }
+
+            fn try_mul(self, other: Self) -> Option<Self> {
+                self.checked_mul(other)
+            }
+
+            fn try_sub(self, other: Self) -> Option<Self> {
+                self.checked_sub(other)
+            }
+
+            fn try_div(self, other: Self) -> Option<Self> {
+                self.checked_div(other)
+            }
+
+            fn try_pow(self, exp: Self) -> Option<Self> {
+                match u32::try_from(exp) {
+                    Ok(exp) => self.checked_pow(exp),
+                    Err(_) if self <= 1 => Some(self),
+                    Err(_) => None,
                 }
-                _ => panic!("wut is {}", operators[col]),
             }
         }
+    )*};
+}
+
+impl_number!(u64, u128);
+
+impl Number for BigUint {
+    fn from_u64(value: u64) -> Self {
+        BigUint::from(value)
+    }
+
+    fn from_big(value: &BigUint) -> Option<Self> {
+        Some(value.clone())
+    }
+
+    fn try_add(self, other: Self) -> Option<Self> {
+        Some(self + &other)
+    }
+
+    fn try_mul(self, other: Self) -> Option<Self> {
+        Some(&self * &other)
+    }
+
+    fn try_sub(self, other: Self) -> Option<Self> {
+        self.checked_sub(&other)
+    }
+
+    fn try_div(self, other: Self) -> Option<Self> {
+        self.checked_div_rem(&other).map(|(quotient, _)| quotient)
+    }
+
+    /// Answers beyond MAX_POW_BITS are refused as an overflow, as BigUint multiplies the slow
+    /// way and anything much bigger would take seconds to minutes
+    fn try_pow(self, exp: Self) -> Option<Self> {
+        if self <= BigUint::from(1) {
+            return Some(self);
+        }
+
+        exp.to_u64()
+            .filter(|&exp| self.max_bits().saturating_mul(exp) <= MAX_POW_BITS)
+            .map(|exp| self.pow(exp))
     }
+}
 
-    results.iter().sum()
+#[derive(Debug, Eq, PartialEq)]
+enum EvalError {
+    /// Subtraction would go below zero
+    Negative,
+    DivisionByZero,
+    /// Only + and * have an answer without any operands
+    NoOperands,
+    /// The answer doesn't fit in the number type being used
+    Overflow,
+    /// An operand doesn't fit in the number type being used
+    OperandOverflow,
 }
 
-fn part_2(s: &str) -> u64 {
-    // Now uses an iterator per line to step each column, and performing operation when it changes
-    // far fewer allocations and simpler to read.
+#[derive(Debug, Eq, PartialEq)]
+enum WorksheetError {
+    /// The problem starting at `column` has no operator under it, or there's no worksheet
+    /// at all and `column` is 0
+    MissingOperator {
+        column: usize,
+    },
+    UnknownOperator {
+        op: char,
+        column: usize,
+    },
+    /// A problem already has an operator, so there can't be another at `column`
+    ExtraOperator {
+        column: usize,
+    },
+    /// The cell at `row`, `column` can't be part of a number, either because it isn't a digit
+    /// or because it starts a second number in the same row
+    InvalidOperand {
+        row: usize,
+        column: usize,
+    },
+    /// The problem at index `problem` couldn't be evaluated
+    Evaluation {
+        problem: usize,
+        error: EvalError,
+    },
+    /// Adding the answer of the problem at index `problem` overflowed the total
+    TotalOverflow {
+        problem: usize,
+    },
+}
 
-    let mut line_iters: Vec<_> = s.lines().map(|l| l.chars()).collect();
-    let mut sum = 0;
+#[derive(Debug)]
+enum StreamError {
+    Io(io::Error),
+    Worksheet(WorksheetError),
+}
 
-    let mut current_op: Option<char> = None;
-    let mut current_nums: Vec<u64> = Vec::new();
+impl From<io::Error> for StreamError {
+    fn from(error: io::Error) -> Self {
+        StreamError::Io(error)
+    }
+}
 
-    loop {
-        let col = line_iters
-            .iter_mut()
-            .map(|l| l.next())
-            .collect::<Vec<Option<char>>>();
+impl From<WorksheetError> for StreamError {
+    fn from(error: WorksheetError) -> Self {
+        StreamError::Worksheet(error)
+    }
+}
+
+/// How the numbers of a problem are written on the worksheet
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Reading {
+    /// One number per row, as humans write them
+    Horizontal,
+    /// One number per column, digits top to bottom, problems read right to left
+    Vertical,
+}
+
+/// How numbers are lined up when rendering a worksheet
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Align {
+    /// Horizontal numbers padded on the right, vertical numbers starting at the top row
+    Left,
+    /// Horizontal numbers padded on the left, vertical numbers ending at the bottom row
+    Right,
+}
+
+/// A number read off the worksheet. Almost all of them fit in a u64, anything longer is kept
+/// exactly so the wider number types can still evaluate it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Operand {
+    Small(u64),
+    Big(BigUint),
+}
+
+impl Operand {
+    /// Puts another digit on the end of the operand
+    fn push_digit(&mut self, digit: u8) {
+        match self {
+            Operand::Small(value) => {
+                match value
+                    .checked_mul(10)
+                    .and_then(|value| value.checked_add(digit as u64))
+                {
+                    Some(next) => *value = next,
+                    None => *self = Operand::Big(push_big_digit(BigUint::from(*value), digit)),
+                }
+            }
+            Operand::Big(value) => *value = push_big_digit(std::mem::take(value), digit),
+        }
+    }
+
+    fn to_number<N: Number>(&self) -> Result<N, EvalError> {
+        match self {
+            Operand::Small(value) => Ok(N::from_u64(*value)),
+            Operand::Big(value) => N::from_big(value).ok_or(EvalError::OperandOverflow),
+        }
+    }
+}
+
+/// Kept out of the way of the u64 path, which is all the puzzle input needs
+#[cold]
+fn push_big_digit(value: BigUint, digit: u8) -> BigUint {
+    &value * &BigUint::from(10) + &BigUint::from(digit as u64)
+}
+
+impl From<u64> for Operand {
+    fn from(value: u64) -> Self {
+        Operand::Small(value)
+    }
+}
+
+impl PartialEq<u64> for Operand {
+    fn eq(&self, other: &u64) -> bool {
+        *self == Operand::Small(*other)
+    }
+}
 
-        if col.iter().all(Option::is_none) {
-            sum += calculate(current_op.unwrap(), &current_nums);
-            current_nums.clear();
-            break;
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Small(value) => value.fmt(f),
+            Operand::Big(value) => f.pad(&value.to_string()),
         }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Problem {
+    op: Op,
+    operands: Vec<Operand>,
+    /// The worksheet columns the problem occupies
+    column_span: Range<usize>,
+}
 
-        if let Some(op) = col.last().unwrap()
-            && !op.is_ascii_whitespace()
+impl Problem {
+    /// Builds the problem from the cells in its columns, `cell(row, column)` giving the cell
+    /// at that position and row `rows` being the operator line
+    fn from_cells(
+        column_span: Range<usize>,
+        rows: usize,
+        cell: impl Fn(usize, usize) -> u8,
+        reading: Reading,
+    ) -> Result<Self, WorksheetError> {
+        let (op_column, op) = column_span
+            .clone()
+            .map(|col| (col, cell(rows, col) as char))
+            .find(|(_, ch)| !ch.is_ascii_whitespace())
+            .ok_or(WorksheetError::MissingOperator {
+                column: column_span.start,
+            })?;
+        let op = Op::try_from(op).map_err(|op| WorksheetError::UnknownOperator {
+            op,
+            column: op_column,
+        })?;
+        if let Some(column) =
+            (op_column + 1..column_span.end).find(|&col| !cell(rows, col).is_ascii_whitespace())
         {
-            if current_op.is_some() {
-                sum += calculate(current_op.unwrap(), &current_nums);
-                current_op = Some(*op);
-                current_nums.clear();
-            } else {
-                current_op = Some(*op);
-            }
+            return Err(WorksheetError::ExtraOperator { column });
         }
 
-        if let Some(num) = digits_to_num(&col[0..col.len() - 1]) {
-            current_nums.push(num);
+        let operands = match reading {
+            Reading::Horizontal => (0..rows)
+                .filter_map(|row| {
+                    digits_to_num(
+                        reading,
+                        column_span.clone().map(|col| (row, col, cell(row, col))),
+                    )
+                    .transpose()
+                })
+                .collect::<Result<_, _>>()?,
+            Reading::Vertical => column_span
+                .clone()
+                .rev()
+                .filter_map(|col| {
+                    digits_to_num(reading, (0..rows).map(|row| (row, col, cell(row, col))))
+                        .transpose()
+                })
+                .collect::<Result<_, _>>()?,
+        };
+
+        Ok(Self {
+            op,
+            operands,
+            column_span,
+        })
+    }
+
+    fn evaluate(&self) -> Result<u64, EvalError> {
+        self.evaluate_as()
+    }
+
+    fn evaluate_as<N: Number>(&self) -> Result<N, EvalError> {
+        let mut operands = self.operands.iter().map(Operand::to_number);
+
+        let Some(first) = operands.next() else {
+            return self.op.identity().ok_or(EvalError::NoOperands);
+        };
+
+        operands.try_fold(first?, |acc, value| self.op.apply(acc, value?))
+    }
+
+    /// How many columns the problem takes up when rendered
+    fn render_width(&self, reading: Reading) -> usize {
+        let width = match reading {
+            Reading::Horizontal => self
+                .operands
+                .iter()
+                .map(|operand| operand.to_string().len())
+                .max()
+                .unwrap_or(0),
+            Reading::Vertical => self.operands.len(),
+        };
+
+        // always room for the operator
+        width.max(1)
+    }
+
+    /// The number rows for the problem, each exactly render_width wide
+    fn render(&self, reading: Reading, align: Align) -> Vec<String> {
+        let width = self.render_width(reading);
+
+        match reading {
+            Reading::Horizontal => self
+                .operands
+                .iter()
+                .map(|operand| match align {
+                    Align::Left => format!("{operand:<width$}"),
+                    Align::Right => format!("{operand:>width$}"),
+                })
+                .collect(),
+            Reading::Vertical => {
+                let digits: Vec<Vec<char>> = self
+                    .operands
+                    .iter()
+                    .map(|operand| operand.to_string().chars().collect())
+                    .collect();
+                let height = digits.iter().map(Vec::len).max().unwrap_or(0);
+
+                (0..height)
+                    .map(|row| {
+                        // the first operand is the rightmost column
+                        (0..width)
+                            .map(|col| {
+                                let Some(digits) = digits.get(width - 1 - col) else {
+                                    return ' ';
+                                };
+                                let offset = match align {
+                                    Align::Left => 0,
+                                    Align::Right => height - digits.len(),
+                                };
+
+                                row.checked_sub(offset)
+                                    .and_then(|i| digits.get(i))
+                                    .copied()
+                                    .unwrap_or(' ')
+                            })
+                            .collect()
+                    })
+                    .collect()
+            }
         }
     }
+}
 
-    sum
+#[derive(Debug, Eq, PartialEq)]
+struct Worksheet {
+    problems: Vec<Problem>,
 }
 
-fn calculate(op: char, values: &[u64]) -> u64 {
-    match op {
-        '+' => values.iter().sum(),
-        '*' => values.iter().product(),
-        op => panic!("wut is {op}"),
+impl Worksheet {
+    /// Splits the worksheet into problems at the columns that are blank on every line, the
+    /// last line holding each problem's operator
+    fn parse(s: &str, reading: Reading) -> Result<Self, WorksheetError> {
+        let lines: Vec<&[u8]> = s.lines().map(str::as_bytes).collect();
+        let Some(rows) = lines.len().checked_sub(1) else {
+            return Err(WorksheetError::MissingOperator { column: 0 });
+        };
+        let width = lines.iter().map(|l| l.len()).max().unwrap();
+
+        let cell = |row: usize, col: usize| lines[row].get(col).copied().unwrap_or(b' ');
+        let is_blank = |col: usize| (0..=rows).all(|row| cell(row, col).is_ascii_whitespace());
+
+        let mut problems = Vec::new();
+        let mut col = 0;
+
+        while col < width {
+            if is_blank(col) {
+                col += 1;
+                continue;
+            }
+
+            let start = col;
+            while col < width && !is_blank(col) {
+                col += 1;
+            }
+
+            problems.push(Problem::from_cells(start..col, rows, cell, reading)?);
+        }
+
+        Ok(Self { problems })
+    }
+
+    /// The sum of every problem's answer
+    fn evaluate(&self) -> Result<u64, WorksheetError> {
+        self.evaluate_as()
+    }
+
+    /// The sum of every problem's answer, worked out in N, so any overflow is reported
+    /// along with the problem that caused it
+    fn evaluate_as<N: Number>(&self) -> Result<N, WorksheetError> {
+        self.problems
+            .iter()
+            .enumerate()
+            .try_fold(N::from_u64(0), |total, (problem, p)| {
+                let answer = p
+                    .evaluate_as()
+                    .map_err(|error| WorksheetError::Evaluation { problem, error })?;
+
+                total
+                    .try_add(answer)
+                    .ok_or(WorksheetError::TotalOverflow { problem })
+            })
+    }
+
+    /// Lays the problems out as worksheet text that parses back to the same problems, with a
+    /// blank column between problems and each operator under its problem's leftmost column.
+    /// The column spans are ignored, they come from the new layout.
+    fn render(&self, reading: Reading, align: Align) -> String {
+        let blocks: Vec<Vec<String>> = self
+            .problems
+            .iter()
+            .map(|problem| problem.render(reading, align))
+            .collect();
+        let height = blocks.iter().map(Vec::len).max().unwrap_or(0);
+
+        let mut lines = vec![String::new(); height + 1];
+
+        for (i, (problem, block)) in self.problems.iter().zip(&blocks).enumerate() {
+            let width = problem.render_width(reading);
+
+            for (row, line) in lines.iter_mut().enumerate() {
+                if i > 0 {
+                    line.push(' ');
+                }
+
+                match block.get(row) {
+                    Some(cells) => line.push_str(cells),
+                    None if row == height => {
+                        line.push(problem.op.into());
+                        line.push_str(&" ".repeat(width - 1));
+                    }
+                    None => line.push_str(&" ".repeat(width)),
+                }
+            }
+        }
+
+        lines.join("\n")
     }
 }
 
-fn digits_to_num(digits: &[Option<char>]) -> Option<u64> {
-    let result = digits
-        .iter()
-        .filter_map(|&c| {
-            if let Some(c) = c
-                && c.is_ascii_digit()
-            {
-                Some(c as u8 - b'0')
-            } else {
-                None
+/// One line of a worksheet, read a cell at a time
+struct LineCells<R> {
+    reader: R,
+    done: bool,
+}
+
+impl<R: BufRead> LineCells<R> {
+    /// The next cell, or None once the line has ended
+    fn next_cell(&mut self) -> io::Result<Option<u8>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let cell = self.reader.fill_buf()?.first().copied();
+        match cell {
+            Some(b'\n') | None => {
+                self.done = true;
+                Ok(None)
+            }
+            Some(cell) => {
+                self.reader.consume(1);
+                Ok(Some(cell))
             }
+        }
+    }
+}
+
+/// Works through the worksheet a column at a time, given a reader per line with the operator
+/// line last, handing each problem over as soon as its last column has been read. Only the
+/// columns of the current problem are held in memory, however wide the worksheet is.
+fn for_each_problem<R: BufRead>(
+    readers: Vec<R>,
+    reading: Reading,
+    mut on_problem: impl FnMut(Problem) -> Result<(), WorksheetError>,
+) -> Result<(), StreamError> {
+    if readers.is_empty() {
+        return Err(WorksheetError::MissingOperator { column: 0 }.into());
+    }
+
+    let mut lines: Vec<LineCells<R>> = readers
+        .into_iter()
+        .map(|reader| LineCells {
+            reader,
+            done: false,
         })
-        .fold(0, |acc, digit| acc * 10 + digit as u64);
+        .collect();
+    let mut columns: Vec<Vec<u8>> = Vec::new();
+    let mut start = 0;
+    let mut col = 0;
+
+    loop {
+        let mut cells = Vec::with_capacity(lines.len());
+        let mut ended = true;
+
+        for line in &mut lines {
+            let cell = line.next_cell()?;
+            ended &= cell.is_none();
+            cells.push(cell.unwrap_or(b' '));
+        }
+
+        if ended || cells.iter().all(u8::is_ascii_whitespace) {
+            if !columns.is_empty() {
+                let cell = |row: usize, col: usize| columns[col - start][row];
+                let rows = lines.len() - 1;
+
+                on_problem(Problem::from_cells(start..col, rows, cell, reading)?)?;
+                columns.clear();
+            }
+
+            if ended {
+                return Ok(());
+            }
+        } else {
+            if columns.is_empty() {
+                start = col;
+            }
+            columns.push(cells);
+        }
+
+        col += 1;
+    }
+}
+
+/// The sum of every problem's answer, evaluating each problem as soon as it has been read and
+/// passing it to on_answer along with its index and answer
+fn evaluate_streaming<R: BufRead>(
+    readers: Vec<R>,
+    reading: Reading,
+    mut on_answer: impl FnMut(usize, &Problem, u64),
+) -> Result<u64, StreamError> {
+    let mut total = 0u64;
+    let mut index = 0;
+
+    for_each_problem(readers, reading, |problem| {
+        let answer = problem
+            .evaluate()
+            .map_err(|error| WorksheetError::Evaluation {
+                problem: index,
+                error,
+            })?;
+        total = total
+            .checked_add(answer)
+            .ok_or(WorksheetError::TotalOverflow { problem: index })?;
+
+        on_answer(index, &problem, answer);
+        index += 1;
+        Ok(())
+    })?;
 
-    if result > 0 { Some(result) } else { None }
+    Ok(total)
+}
+
+fn part_1(s: &str) -> u64 {
+    Worksheet::parse(s, Reading::Horizontal)
+        .and_then(|worksheet| worksheet.evaluate())
+        .unwrap()
+}
+
+fn part_2(s: &str) -> u64 {
+    Worksheet::parse(s, Reading::Vertical)
+        .and_then(|worksheet| worksheet.evaluate())
+        .unwrap()
+}
+
+/// Reads an operand's cells, each with its row and column, in reading order as a number. Blank
+/// cells can only pad either end of a row, but can be anywhere in a column, as the shorter
+/// numbers in the rows leave gaps in the columns. Only an operand without any digits is None,
+/// so a column of zeros is still Some(0).
+fn digits_to_num(
+    reading: Reading,
+    cells: impl IntoIterator<Item = (usize, usize, u8)>,
+) -> Result<Option<Operand>, WorksheetError> {
+    let mut value: Option<Operand> = None;
+    let mut ended = false;
+
+    for (row, column, cell) in cells {
+        if cell.is_ascii_whitespace() {
+            ended |= reading == Reading::Horizontal && value.is_some();
+            continue;
+        }
+        if ended || !cell.is_ascii_digit() {
+            return Err(WorksheetError::InvalidOperand { row, column });
+        }
+
+        let digit = cell - b'0';
+        match &mut value {
+            Some(value) => value.push_digit(digit),
+            None => value = Some(Operand::Small(digit as u64)),
+        }
+    }
+
+    Ok(value)
 }
 
 #[cfg(test)]
 mod tests {
+    use std::io::BufReader;
+
     use super::*;
-    use crate::bench;
+    use crate::{Rng, bench};
 
     #[test]
     fn test_day_6_part_1_sample() {
@@ -106,6 +726,663 @@ mod tests {
         assert_eq!(part_1(include_str!("assets/day_6.txt")), 4412382293768);
     }
 
+    #[test]
+    fn test_day_6_parse_sample() {
+        let s = include_str!("assets/day_6_sample.txt");
+
+        let horizontal = Worksheet::parse(s, Reading::Horizontal).unwrap();
+        assert_eq!(
+            horizontal.problems[0],
+            Problem {
+                op: Op::Mul,
+                operands: to_operands(&[123, 45, 6]),
+                column_span: 0..3
+            }
+        );
+        assert_eq!(
+            horizontal.problems[3],
+            Problem {
+                op: Op::Add,
+                operands: to_operands(&[64, 23, 314]),
+                column_span: 12..15
+            }
+        );
+
+        let vertical = Worksheet::parse(s, Reading::Vertical).unwrap();
+        assert_eq!(
+            vertical
+                .problems
+                .iter()
+                .map(|p| (p.op, p.operands.clone(), p.column_span.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (Op::Mul, to_operands(&[356, 24, 1]), 0..3),
+                (Op::Add, to_operands(&[8, 248, 369]), 4..7),
+                (Op::Mul, to_operands(&[175, 581, 32]), 8..11),
+                (Op::Add, to_operands(&[4, 431, 623]), 12..15),
+            ]
+        );
+        assert_eq!(
+            vertical
+                .problems
+                .iter()
+                .map(|p| p.evaluate().unwrap())
+                .collect::<Vec<_>>(),
+            vec![8544, 625, 3253600, 1058]
+        );
+    }
+
+    #[test]
+    fn test_day_6_extended_operators() {
+        let s = "20 120  2 5 3\n 5   7 10 9 2\n 3   2  3 7 2\n-  /   <  > ^";
+
+        let answers = |reading| -> Vec<Result<u64, EvalError>> {
+            Worksheet::parse(s, reading)
+                .unwrap()
+                .problems
+                .iter()
+                .map(Problem::evaluate)
+                .collect()
+        };
+
+        assert_eq!(
+            answers(Reading::Horizontal),
+            vec![Ok(12), Ok(8), Ok(2), Ok(9), Ok(81)]
+        );
+        // 53 - 2, 72 / 2 / 1, min(203, 1), max(597), 322
+        assert_eq!(
+            answers(Reading::Vertical),
+            vec![Ok(51), Ok(36), Ok(1), Ok(597), Ok(322)]
+        );
+    }
+
+    #[test]
+    fn test_day_6_evaluation_errors() {
+        let worksheet = |s| Worksheet::parse(s, Reading::Horizontal).unwrap();
+
+        assert_eq!(
+            worksheet("1 2 3\n2 0 4\n+ / -").evaluate(),
+            Err(WorksheetError::Evaluation {
+                problem: 1,
+                error: EvalError::DivisionByZero
+            })
+        );
+        assert_eq!(
+            worksheet("1 3\n2 4\n+ -").evaluate(),
+            Err(WorksheetError::Evaluation {
+                problem: 1,
+                error: EvalError::Negative
+            })
+        );
+    }
+
+    #[test]
+    fn test_day_6_unknown_operator() {
+        for reading in [Reading::Horizontal, Reading::Vertical] {
+            assert_eq!(
+                Worksheet::parse("12 34\n 5 67\n+  % ", reading),
+                Err(WorksheetError::UnknownOperator { op: '%', column: 3 })
+            );
+            assert_eq!(
+                Worksheet::parse("12 34\n 5 67\n+    ", reading),
+                Err(WorksheetError::MissingOperator { column: 3 })
+            );
+            assert_eq!(
+                Worksheet::parse("12\n+*", reading),
+                Err(WorksheetError::ExtraOperator { column: 1 })
+            );
+            assert_eq!(
+                Worksheet::parse("", reading),
+                Err(WorksheetError::MissingOperator { column: 0 })
+            );
+        }
+    }
+
+    #[test]
+    fn test_day_6_overflow() {
+        let s = "5 18446744073709551615 4294967296\n6                    1 4294967296\n+ +                    *";
+        let worksheet = Worksheet::parse(s, Reading::Horizontal).unwrap();
+
+        assert_eq!(
+            worksheet.evaluate(),
+            Err(WorksheetError::Evaluation {
+                problem: 1,
+                error: EvalError::Overflow
+            })
+        );
+        assert_eq!(worksheet.problems[2].evaluate(), Err(EvalError::Overflow));
+        assert_eq!(
+            worksheet.evaluate_as::<u128>(),
+            Ok(11 + u64::MAX as u128 + 1 + (1 << 64))
+        );
+        assert_eq!(
+            worksheet.evaluate_as::<BigUint>().unwrap().to_string(),
+            "36893488147419103243"
+        );
+
+        let s = "18446744073709551615 1\n+                    +";
+        assert_eq!(
+            Worksheet::parse(s, Reading::Horizontal).unwrap().evaluate(),
+            Err(WorksheetError::TotalOverflow { problem: 1 })
+        );
+    }
+
+    #[test]
+    fn test_day_6_big_evaluation() {
+        let s = "18446744073709551615 2 7\n18446744073709551615 9 2\n18446744073709551615 9 0\n*                    ^ ^";
+        let worksheet = Worksheet::parse(s, Reading::Horizontal).unwrap();
+
+        assert_eq!(
+            worksheet.evaluate_as::<u128>(),
+            Err(WorksheetError::Evaluation {
+                problem: 0,
+                error: EvalError::Overflow
+            })
+        );
+        // (2^64 - 1)^3 + (2^9)^9 + 49^0
+        assert_eq!(
+            worksheet.evaluate_as::<BigUint>().unwrap().to_string(),
+            "6277101735386680762814942322444853443619211083648207945728"
+        );
+    }
+
+    #[test]
+    fn test_day_6_big_pow_limit() {
+        let huge = Worksheet::parse("2\n4294967295\n^", Reading::Horizontal).unwrap();
+        assert_eq!(
+            huge.evaluate_as::<BigUint>(),
+            Err(WorksheetError::Evaluation {
+                problem: 0,
+                error: EvalError::Overflow
+            })
+        );
+
+        // 2 takes 2 bits, so 2^(MAX_POW_BITS / 2) is as far as it goes
+        let pow = |exp: u64| {
+            Worksheet::parse(&format!("2\n{exp}\n^"), Reading::Horizontal)
+                .unwrap()
+                .evaluate_as::<BigUint>()
+        };
+        assert!(pow(MAX_POW_BITS / 2) == Ok(BigUint::from(2).pow(MAX_POW_BITS / 2)));
+        assert!(pow(MAX_POW_BITS / 2 + 1).is_err());
+
+        // 0 and 1 never grow, so any exponent is fine
+        let big = "9".repeat(20);
+        let ones = Worksheet::parse(
+            &format!("1{0:20}0\n{big} {big}\n^{0:20}^", ""),
+            Reading::Horizontal,
+        );
+        assert_eq!(ones.unwrap().evaluate_as::<BigUint>(), Ok(BigUint::from(1)));
+    }
+
+    #[test]
+    fn test_day_6_zero_operands() {
+        let s = "10 0  0 1\n20 0 00 2\n*  + *  *";
+
+        let operands = |reading| -> Vec<Vec<Operand>> {
+            Worksheet::parse(s, reading)
+                .unwrap()
+                .problems
+                .into_iter()
+                .map(|p| p.operands)
+                .collect()
+        };
+
+        assert_eq!(
+            operands(Reading::Horizontal),
+            vec![vec![10, 20], vec![0, 0], vec![0, 0], vec![1, 2]]
+        );
+        assert_eq!(
+            operands(Reading::Vertical),
+            vec![vec![0, 12], vec![0], vec![0, 0], vec![12]]
+        );
+
+        assert_eq!(part_1(s), 200 + 2);
+        assert_eq!(part_2(s), 12);
+
+        // a leading zero used to be mistaken for a product that hadn't started yet
+        assert_eq!(part_1("0\n5\n*"), 0);
+    }
+
+    #[test]
+    fn test_day_6_digits_to_num() {
+        let column = |cells: &[u8]| {
+            digits_to_num(
+                Reading::Vertical,
+                cells.iter().enumerate().map(|(row, &cell)| (row, 0, cell)),
+            )
+        };
+        let row = |cells: &[u8]| {
+            digits_to_num(
+                Reading::Horizontal,
+                cells.iter().enumerate().map(|(col, &cell)| (0, col, cell)),
+            )
+        };
+
+        assert_eq!(column(b"   "), Ok(None));
+        assert_eq!(column(b"0 "), Ok(Some(Operand::Small(0))));
+        assert_eq!(column(b"00"), Ok(Some(Operand::Small(0))));
+        assert_eq!(column(b" 40"), Ok(Some(Operand::Small(40))));
+        assert_eq!(column(b"4 0"), Ok(Some(Operand::Small(40))));
+        assert_eq!(row(b" 40 "), Ok(Some(Operand::Small(40))));
+        assert_eq!(
+            row(b"4 0"),
+            Err(WorksheetError::InvalidOperand { row: 0, column: 2 })
+        );
+        assert_eq!(
+            column(b" 4a"),
+            Err(WorksheetError::InvalidOperand { row: 2, column: 0 })
+        );
+    }
+
+    #[test]
+    fn test_day_6_operand_overflow() {
+        let nines = "9\n".repeat(40) + "+";
+        let worksheet = Worksheet::parse(&nines, Reading::Vertical).unwrap();
+        assert_eq!(
+            worksheet.evaluate(),
+            Err(WorksheetError::Evaluation {
+                problem: 0,
+                error: EvalError::OperandOverflow
+            })
+        );
+        assert_eq!(
+            worksheet.evaluate_as::<u128>(),
+            Err(WorksheetError::Evaluation {
+                problem: 0,
+                error: EvalError::OperandOverflow
+            })
+        );
+        assert_eq!(
+            worksheet.evaluate_as::<BigUint>().unwrap().to_string(),
+            "9".repeat(40)
+        );
+        assert_eq!(worksheet.render(Reading::Vertical, Align::Right), nines);
+
+        let worksheet = Worksheet::parse(&("9\n".repeat(20) + "*"), Reading::Vertical).unwrap();
+        assert_eq!(worksheet.evaluate_as::<u128>(), Ok(99999999999999999999));
+
+        let worksheet =
+            Worksheet::parse("1\n 99999999999999999999\n+", Reading::Horizontal).unwrap();
+        assert_eq!(
+            worksheet.evaluate(),
+            Err(WorksheetError::Evaluation {
+                problem: 0,
+                error: EvalError::OperandOverflow
+            })
+        );
+        assert_eq!(
+            worksheet.evaluate_as::<BigUint>().unwrap().to_string(),
+            "100000000000000000000"
+        );
+
+        assert_eq!(
+            Worksheet::parse("18446744073709551615\n+", Reading::Horizontal)
+                .unwrap()
+                .problems[0]
+                .operands,
+            vec![u64::MAX]
+        );
+    }
+
+    #[test]
+    fn test_day_6_invalid_operand() {
+        for reading in [Reading::Horizontal, Reading::Vertical] {
+            assert_eq!(
+                Worksheet::parse("1a\n34\n+ ", reading),
+                Err(WorksheetError::InvalidOperand { row: 0, column: 1 })
+            );
+        }
+
+        assert_eq!(
+            Worksheet::parse("1 2\n345\n+  ", Reading::Horizontal),
+            Err(WorksheetError::InvalidOperand { row: 0, column: 2 })
+        );
+        assert_eq!(
+            Worksheet::parse("1 \n 3\n45\n+ ", Reading::Vertical)
+                .unwrap()
+                .problems[0]
+                .operands,
+            vec![35, 14]
+        );
+    }
+
+    fn to_operands(values: &[u64]) -> Vec<Operand> {
+        values.iter().copied().map(Operand::from).collect()
+    }
+
+    /// Problems that stay well inside u64 when evaluated with any operator
+    fn random_problems(rng: &mut Rng, count: usize, ops: &[Op]) -> Vec<Problem> {
+        (0..count)
+            .map(|_| {
+                let op = ops[rng.below(ops.len() as u64) as usize];
+                let operands = (0..1 + rng.below(4))
+                    .map(|_| {
+                        let digits = 1 + rng.below(4) as u32;
+                        Operand::from(rng.below(10u64.pow(digits)))
+                    })
+                    .collect();
+
+                Problem {
+                    op,
+                    operands,
+                    column_span: 0..0,
+                }
+            })
+            .collect()
+    }
+
+    fn ops_and_operands(worksheet: &Worksheet) -> Vec<(Op, &[Operand])> {
+        worksheet
+            .problems
+            .iter()
+            .map(|p| (p.op, p.operands.as_slice()))
+            .collect()
+    }
+
+    #[test]
+    fn test_day_6_render_sample() {
+        let worksheet =
+            Worksheet::parse(include_str!("assets/day_6_sample.txt"), Reading::Vertical).unwrap();
+
+        assert_eq!(
+            worksheet.render(Reading::Vertical, Align::Right),
+            [
+                "  3 32   51 64 ",
+                " 25 64  387 23 ",
+                "146 988 215 314",
+                "*   +   *   +  "
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            worksheet.render(Reading::Horizontal, Align::Left),
+            [
+                "356 8   175 4  ",
+                "24  248 581 431",
+                "1   369 32  623",
+                "*   +   *   +  "
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_day_6_render_round_trip() {
+        let mut rng = Rng(0x2025_0006);
+        let ops = [
+            Op::Add,
+            Op::Mul,
+            Op::Sub,
+            Op::Div,
+            Op::Min,
+            Op::Max,
+            Op::Pow,
+        ];
+
+        for _ in 0..200 {
+            let count = 1 + rng.below(8) as usize;
+            let worksheet = Worksheet {
+                problems: random_problems(&mut rng, count, &ops),
+            };
+
+            for reading in [Reading::Horizontal, Reading::Vertical] {
+                for align in [Align::Left, Align::Right] {
+                    let text = worksheet.render(reading, align);
+                    let parsed = Worksheet::parse(&text, reading).unwrap();
+
+                    assert_eq!(
+                        ops_and_operands(&parsed),
+                        ops_and_operands(&worksheet),
+                        "{text}"
+                    );
+                    assert_eq!(parsed.render(reading, align), text);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_day_6_part_2_synthetic() {
+        let mut rng = Rng(0x2025_0066);
+        let worksheet = Worksheet {
+            problems: random_problems(&mut rng, 2000, &[Op::Add, Op::Mul]),
+        };
+        let text = worksheet.render(Reading::Vertical, Align::Right);
+        let expected = worksheet.evaluate().unwrap();
+
+        bench(100, || {
+            assert_eq!(part_2(&text), expected);
+        });
+    }
+
+    /// Problems as plain ops and operands, so cases are easy to copy while shrinking
+    type Case = Vec<(Op, Vec<u64>)>;
+
+    /// A number with `digits` digits, none of them zero, like every number in the puzzle input
+    fn zero_free_number(rng: &mut Rng, digits: u64) -> u64 {
+        (0..digits).fold(0, |acc, _| acc * 10 + 1 + rng.below(9))
+    }
+
+    /// A worksheet shaped like the puzzle input, which the old implementation can read: only
+    /// `+` and `*`, the same number of operands in every problem and numbers of mixed widths
+    fn random_oracle_case(rng: &mut Rng) -> Case {
+        let rows = 1 + rng.below(4);
+
+        (0..1 + rng.below(6))
+            .map(|_| {
+                let op = [Op::Add, Op::Mul][rng.below(2) as usize];
+                let operands = (0..rows)
+                    .map(|_| {
+                        let digits = 1 + rng.below(4);
+                        zero_free_number(rng, digits)
+                    })
+                    .collect();
+
+                (op, operands)
+            })
+            .collect()
+    }
+
+    /// Writes the case out a number per row, so part 2 has to cope with the blank cells left by
+    /// the shorter numbers
+    fn render_case(case: &Case, align: Align) -> String {
+        let worksheet = Worksheet {
+            problems: case
+                .iter()
+                .map(|(op, operands)| Problem {
+                    op: *op,
+                    operands: to_operands(operands),
+                    column_span: 0..0,
+                })
+                .collect(),
+        };
+
+        worksheet.render(Reading::Horizontal, align)
+    }
+
+    /// Whether day_6 and the old implementation disagree on part 2, counting a panic as an
+    /// answer
+    fn disagrees_with_oracle(case: &Case, align: Align) -> bool {
+        let text = render_case(case, align);
+        let ours = std::panic::catch_unwind(|| part_2(&text)).ok();
+        let theirs = std::panic::catch_unwind(|| crate::day_6_bad::part_2(&text)).ok();
+
+        ours != theirs
+    }
+
+    /// Smaller cases that are still shaped like the puzzle input
+    fn shrink_candidates(case: &Case) -> Vec<Case> {
+        let mut candidates = Vec::new();
+
+        if case[0].1.len() > 1 {
+            for j in 0..case[0].1.len() {
+                let mut candidate = case.clone();
+                for (_, operands) in &mut candidate {
+                    operands.remove(j);
+                }
+                candidates.push(candidate);
+            }
+        }
+
+        for i in 0..case.len() {
+            if case.len() > 1 {
+                let mut candidate = case.clone();
+                candidate.remove(i);
+                candidates.push(candidate);
+            }
+
+            if case[i].0 == Op::Mul {
+                let mut candidate = case.clone();
+                candidate[i].0 = Op::Add;
+                candidates.push(candidate);
+            }
+
+            for j in 0..case[i].1.len() {
+                let operand = case[i].1[j];
+                let ones = (0..operand.to_string().len()).fold(0, |acc, _| acc * 10 + 1);
+
+                if operand >= 10 {
+                    let mut candidate = case.clone();
+                    candidate[i].1[j] = operand / 10;
+                    candidates.push(candidate);
+                }
+
+                if operand != ones {
+                    let mut candidate = case.clone();
+                    candidate[i].1[j] = ones;
+                    candidates.push(candidate);
+                }
+            }
+        }
+
+        candidates
+    }
+
+    /// Greedily takes the first smaller case that still fails until none do
+    fn shrink(mut case: Case, fails: impl Fn(&Case) -> bool) -> Case {
+        'shrinking: loop {
+            for candidate in shrink_candidates(&case) {
+                if fails(&candidate) {
+                    case = candidate;
+                    continue 'shrinking;
+                }
+            }
+
+            return case;
+        }
+    }
+
+    #[test]
+    fn test_day_6_shrink() {
+        let case = vec![
+            (Op::Add, vec![512, 73, 4]),
+            (Op::Mul, vec![14, 987, 251]),
+            (Op::Mul, vec![3, 21, 9]),
+        ];
+
+        let shrunk = shrink(case, |case| {
+            case.iter()
+                .any(|(op, operands)| *op == Op::Mul && operands.iter().any(|&n| n >= 100))
+        });
+
+        assert_eq!(shrunk, vec![(Op::Mul, vec![111])]);
+    }
+
+    #[test]
+    fn test_day_6_part_2_matches_oracle() {
+        let mut rng = Rng(0x2025_0606);
+
+        for _ in 0..500 {
+            let case = random_oracle_case(&mut rng);
+
+            for align in [Align::Left, Align::Right] {
+                if disagrees_with_oracle(&case, align) {
+                    let shrunk = shrink(case, |case| disagrees_with_oracle(case, align));
+                    panic!(
+                        "day_6 and day_6_bad disagree on:\n{}\n{shrunk:?}",
+                        render_case(&shrunk, align)
+                    );
+                }
+            }
+        }
+    }
+
+    /// A reader per line, each starting at its line's offset into the text and stopping at the
+    /// end of the line, like seeking a handle per line into one big file
+    fn line_readers(text: &str, capacity: usize) -> Vec<BufReader<&[u8]>> {
+        let mut offset = 0;
+
+        text.split_inclusive('\n')
+            .map(|line| {
+                let reader = BufReader::with_capacity(capacity, &text.as_bytes()[offset..]);
+                offset += line.len();
+                reader
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_day_6_streaming() {
+        let sample = include_str!("assets/day_6_sample.txt");
+        let mut answers = Vec::new();
+
+        let total = evaluate_streaming(
+            line_readers(sample, 4),
+            Reading::Vertical,
+            |i, p, answer| {
+                answers.push((i, p.op, p.column_span.clone(), answer));
+            },
+        )
+        .unwrap();
+
+        assert_eq!(total, 3263827);
+        assert_eq!(
+            answers,
+            vec![
+                (0, Op::Mul, 0..3, 8544),
+                (1, Op::Add, 4..7, 625),
+                (2, Op::Mul, 8..11, 3253600),
+                (3, Op::Add, 12..15, 1058),
+            ]
+        );
+
+        let real = include_str!("assets/day_6.txt");
+        for reading in [Reading::Horizontal, Reading::Vertical] {
+            let total = evaluate_streaming(line_readers(real, 64), reading, |_, _, _| ()).unwrap();
+            assert_eq!(
+                total,
+                Worksheet::parse(real, reading).unwrap().evaluate().unwrap()
+            );
+        }
+
+        let crlf = "12  3\r\n 4  56\r\n+   *\r\n";
+        let total = evaluate_streaming(line_readers(crlf, 1), Reading::Horizontal, |_, _, _| ());
+        assert_eq!(total.unwrap(), 12 + 4 + 3 * 56);
+
+        let error =
+            evaluate_streaming(line_readers("1 2\n+ %", 1), Reading::Vertical, |_, _, _| ())
+                .unwrap_err();
+        assert!(matches!(
+            error,
+            StreamError::Worksheet(WorksheetError::UnknownOperator { op: '%', column: 2 })
+        ));
+
+        let error = evaluate_streaming(line_readers("12\n+*", 1), Reading::Vertical, |_, _, _| ())
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            StreamError::Worksheet(WorksheetError::ExtraOperator { column: 1 })
+        ));
+
+        let error =
+            evaluate_streaming(Vec::<&[u8]>::new(), Reading::Vertical, |_, _, _| ()).unwrap_err();
+        assert!(matches!(
+            error,
+            StreamError::Worksheet(WorksheetError::MissingOperator { column: 0 })
+        ));
+    }
+
     #[test]
     fn test_day_6_part_2_sample() {
         assert_eq!(part_2(include_str!("assets/day_6_sample.txt")), 3263827);
@@ -1,4 +1,45 @@
-use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+
+use crate::big_uint::BigUint;
+
+/// A type paths can be counted in, usize, u64 or u128 when a checked count is enough, or BigUint
+/// when the count needs to be exact however deep the manifold is
+trait PathCount: Clone {
+    fn from_u64(value: u64) -> Self;
+    /// None if the count doesn't fit
+    fn try_add(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_path_count {
+    ($($t:ty),*) => {$(
+        impl PathCount for $t {
+            fn from_u64(value: u64) -> Self {
+                value as $t
+            }
+
+            fn try_add(self, other: Self) -> Option<Self> {
+                self.checked_add(other)
+            }
+        }
+    )*};
+}
+
+impl_path_count!(usize, u64, u128);
+
+impl PathCount for BigUint {
+    fn from_u64(value: u64) -> Self {
+        BigUint::from(value)
+    }
+
+    fn try_add(self, other: Self) -> Option<Self> {
+        Some(self + &other)
+    }
+}
+
+/// The number of paths doesn't fit in the type it's being counted in
+#[derive(Debug, Eq, PartialEq)]
+struct PathCountOverflow;
+
 struct Grid {
     data: Vec<char>,
     width: usize,
@@ -19,67 +60,161 @@ impl Grid {
         }
     }
 
+    /// Follows the beams down a row at a time, counting every splitter a beam reaches.
+    /// Beams split out sideways carry straight on down from there.
     fn tachyon_beam_split_count(&self) -> usize {
-        let mut emitters = HashSet::new();
+        let mut beams = vec![false; self.width];
+        let mut next = vec![false; self.width];
+        let mut splits = 0;
 
-        self.track(self.start_x(), 0, &mut emitters);
+        beams[self.start_x()] = true;
 
-        emitters.len()
-    }
+        for y in 0..self.height - 1 {
+            next.fill(false);
+
+            for x in (0..self.width).filter(|&x| beams[x]) {
+                if self.is_splitter(x, y) {
+                    splits += 1;
 
-    fn track(&self, x: usize, y: usize, emitters: &mut HashSet<(usize, usize)>) {
-        for y in y..self.height - 1 {
-            if self.is_splitter(x, y) {
-                if emitters.insert((x, y)) {
                     if x > 0 {
-                        self.track(x - 1, y, emitters);
+                        next[x - 1] = true;
                     }
                     if x < self.width - 1 {
-                        self.track(x + 1, y, emitters);
+                        next[x + 1] = true;
                     }
+                } else {
+                    next[x] = true;
                 }
-                break;
             }
+
+            std::mem::swap(&mut beams, &mut next);
         }
+
+        splits
     }
 
     fn tachyon_path_count(&self) -> usize {
-        let mut count_cache = HashMap::new();
-
-        self.track_path(self.start_x(), 0, &mut count_cache)
+        self.tachyon_path_count_as().unwrap()
     }
 
-    fn track_path(
-        &self,
-        x: usize,
-        y: usize,
-        count_cache: &mut HashMap<(usize, usize), usize>,
-    ) -> usize {
-        if y >= self.height {
-            return 1;
+    /// Works up from the bottom a row at a time, where `below[x]` is how many paths there are
+    /// from column x of the row underneath, and every column past the last row is one path.
+    /// Counts that overflow N are None, which only matters if the start's count is one of them,
+    /// as columns no beam ever reaches are counted too.
+    fn tachyon_path_count_as<N: PathCount>(&self) -> Result<N, PathCountOverflow> {
+        let mut below = vec![Some(N::from_u64(1)); self.width];
+        let mut row = vec![None; self.width];
+
+        for y in (0..self.height).rev() {
+            for x in 0..self.width {
+                row[x] = if self.is_splitter(x, y) {
+                    let left = if x > 0 {
+                        below[x - 1].clone()
+                    } else {
+                        Some(N::from_u64(0))
+                    };
+                    let right = if x < self.width - 1 {
+                        below[x + 1].clone()
+                    } else {
+                        Some(N::from_u64(0))
+                    };
+
+                    left.zip(right)
+                        .and_then(|(left, right)| left.try_add(right))
+                } else {
+                    below[x].clone()
+                };
+            }
+
+            std::mem::swap(&mut below, &mut row);
         }
 
-        if let Some(&count) = count_cache.get(&(x, y)) {
-            return count;
+        below.swap_remove(self.start_x()).ok_or(PathCountOverflow)
+    }
+
+    /// How many timelines pass through each cell, worked down a row at a time like part 2 but
+    /// from the start rather than the bottom, so the last row adds up to the path count. A beam
+    /// hitting a splitter carries its timelines into the cells either side.
+    fn timelines(&self) -> Vec<Vec<BigUint>> {
+        let mut rows = vec![vec![BigUint::zero(); self.width]; self.height];
+        let mut above = vec![BigUint::zero(); self.width];
+
+        above[self.start_x()] = BigUint::from(1);
+
+        for (y, row) in rows.iter_mut().enumerate() {
+            for (x, count) in above.iter().enumerate() {
+                if count.is_zero() {
+                    continue;
+                }
+
+                if !self.is_splitter(x, y) {
+                    row[x] += count;
+                    continue;
+                }
+                if x > 0 {
+                    row[x - 1] += count;
+                }
+                if x < self.width - 1 {
+                    row[x + 1] += count;
+                }
+            }
+
+            above.clone_from(row);
         }
 
-        let count = if self.is_splitter(x, y) {
-            let mut total = 0;
+        rows
+    }
+
+    /// The grid with beams drawn as `|` down to row `up_to`, each of those rows followed by
+    /// the timeline counts of its beams from left to right
+    fn render_frame(&self, timelines: &[Vec<BigUint>], up_to: usize) -> String {
+        let mut frame = String::new();
 
-            if x > 0 {
-                total += self.track_path(x - 1, y, count_cache);
+        for (y, counts) in timelines.iter().enumerate() {
+            let cells = &self.data[y * self.width..(y + 1) * self.width];
+
+            if y > up_to {
+                frame.extend(cells);
+                frame.push('\n');
+                continue;
             }
-            if x < self.width - 1 {
-                total += self.track_path(x + 1, y, count_cache);
+
+            frame.extend(cells.iter().zip(counts).map(|(&cell, count)| {
+                if cell == '.' && !count.is_zero() {
+                    '|'
+                } else {
+                    cell
+                }
+            }));
+
+            let beams: Vec<String> = counts
+                .iter()
+                .filter(|count| !count.is_zero())
+                .map(BigUint::to_string)
+                .collect();
+            if !beams.is_empty() {
+                frame.push_str("  ");
+                frame.push_str(&beams.join(" "));
             }
+            frame.push('\n');
+        }
 
-            total
-        } else {
-            self.track_path(x, y + 1, count_cache)
-        };
+        frame
+    }
+
+    /// Writes a frame per row as the beams spread down the manifold, separated by blank lines,
+    /// to stdout, a file or anything else
+    fn render_beams(&self, out: &mut impl Write) -> io::Result<()> {
+        let timelines = self.timelines();
+
+        for y in 0..self.height {
+            if y > 0 {
+                writeln!(out)?;
+            }
+            write!(out, "{}", self.render_frame(&timelines, y))?;
+        }
 
-        count_cache.insert((x, y), count);
-        count
+        Ok(())
     }
 
     fn is_splitter(&self, x: usize, y: usize) -> bool {
@@ -123,6 +258,123 @@ mod tests {
         assert_eq!(grid.tachyon_path_count(), 40);
     }
 
+    #[test]
+    fn test_day_7_tall_manifold() {
+        let mut s = String::from("..S..\n.....\n..^..\n");
+        for _ in 0..200_000 {
+            s.push_str(".....\n");
+        }
+        s.push_str(".^.^.\n.....\n");
+        let grid = Grid::from_str(&s);
+
+        assert_eq!(grid.tachyon_beam_split_count(), 3);
+        assert_eq!(grid.tachyon_path_count(), 4);
+    }
+
+    /// A triangle of splitters `levels` deep, like the sample's, where every beam hits a
+    /// splitter at every level, so there are 2^levels paths
+    fn splitter_triangle(levels: usize) -> Grid {
+        let width = 2 * levels + 3;
+        let centre = levels + 1;
+        let mut s = String::new();
+
+        s.push_str(&".".repeat(centre));
+        s.push('S');
+        s.push_str(&".".repeat(width - centre - 1));
+        s.push('\n');
+        for level in 0..levels {
+            s.push_str(&".".repeat(width));
+            s.push('\n');
+            s.extend((0..width).map(|x| {
+                let offset = x as isize - centre as isize;
+                if offset.unsigned_abs() <= level && (offset + level as isize) % 2 == 0 {
+                    '^'
+                } else {
+                    '.'
+                }
+            }));
+            s.push('\n');
+        }
+        s.push_str(&".".repeat(width));
+        s.push('\n');
+
+        Grid::from_str(&s)
+    }
+
+    #[test]
+    fn test_day_7_path_count_overflow() {
+        let grid = splitter_triangle(3);
+        assert_eq!(grid.tachyon_beam_split_count(), 1 + 2 + 3);
+        assert_eq!(grid.tachyon_path_count(), 8);
+
+        let grid = splitter_triangle(70);
+        assert_eq!(grid.tachyon_path_count_as::<u64>(), Err(PathCountOverflow));
+        assert_eq!(grid.tachyon_path_count_as::<u128>(), Ok(1 << 70));
+
+        let grid = splitter_triangle(130);
+        assert_eq!(grid.tachyon_path_count_as::<u128>(), Err(PathCountOverflow));
+        assert_eq!(
+            grid.tachyon_path_count_as::<BigUint>(),
+            Ok(BigUint::from(2).pow(130))
+        );
+    }
+
+    #[test]
+    fn test_day_7_timelines() {
+        for (s, paths) in [
+            (include_str!("assets/day_7_sample.txt"), 40),
+            (include_str!("assets/day_7.txt"), 1537373473728),
+        ] {
+            let grid = Grid::from_str(s);
+            let timelines = grid.timelines();
+
+            assert_eq!(
+                timelines.last().unwrap().iter().cloned().sum::<BigUint>(),
+                BigUint::from(paths)
+            );
+        }
+    }
+
+    #[test]
+    fn test_day_7_render_beams() {
+        let grid = Grid::from_str(include_str!("assets/day_7_sample.txt"));
+
+        assert_eq!(
+            grid.render_frame(&grid.timelines(), 5),
+            [
+                ".......S.......  1",
+                ".......|.......  1",
+                "......|^|......  1 1",
+                "......|.|......  1 1",
+                ".....|^|^|.....  1 2 1",
+                ".....|.|.|.....  1 2 1",
+                ".....^.^.^.....",
+                "...............",
+                "....^.^...^....",
+                "...............",
+                "...^.^...^.^...",
+                "...............",
+                "..^...^.....^..",
+                "...............",
+                ".^.^.^.^.^...^.",
+                "...............",
+                "",
+            ]
+            .join("\n")
+        );
+
+        let mut out = Vec::new();
+        grid.render_beams(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let frames: Vec<&str> = out.split("\n\n").collect();
+
+        assert_eq!(frames.len(), grid.height);
+        assert_eq!(
+            frames.last().unwrap().lines().last().unwrap(),
+            "|.|.|.|.|.|||.|  1 2 10 11 11 2 1 1 1"
+        );
+    }
+
     #[test]
     fn test_day_7_part_2_real() {
         bench(1000, || {
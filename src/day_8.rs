@@ -68,7 +68,7 @@ impl Space {
             let p2_circuit = *circuit_lookup.get(p2).unwrap();
 
             if p1_circuit != p2_circuit {
-                for (_, circuit) in circuit_lookup.iter_mut() {
+                for circuit in circuit_lookup.values_mut() {
                     if *circuit == p2_circuit {
                         *circuit = p1_circuit;
                     }
@@ -78,7 +78,7 @@ impl Space {
 
         let mut circuits: HashMap<usize, u32> = HashMap::new();
 
-        for (_, c) in circuit_lookup.iter() {
+        for c in circuit_lookup.values() {
             circuits.entry(*c).and_modify(|v| *v += 1).or_insert(1);
         }
 
@@ -101,7 +101,7 @@ impl Space {
             let p2_circuit = *circuit_lookup.get(p2).unwrap();
 
             if p1_circuit != p2_circuit {
-                for (_, circuit) in circuit_lookup.iter_mut() {
+                for circuit in circuit_lookup.values_mut() {
                     if *circuit == p2_circuit {
                         *circuit = p1_circuit;
                     }
@@ -1,6 +1,6 @@
-#![feature(iter_map_windows)]
 #![allow(dead_code)]
 
+mod big_uint;
 mod day_1;
 mod day_10;
 mod day_2;
@@ -8,6 +8,7 @@ mod day_3;
 mod day_4;
 mod day_5;
 mod day_6;
+#[cfg(test)]
 mod day_6_bad;
 mod day_7;
 mod day_8;
@@ -15,7 +16,7 @@ mod day_9;
 
 use std::time::{Duration, Instant};
 
-pub fn bench(n: u32, f: fn()) {
+pub fn bench(n: u32, f: impl Fn()) {
     let mut sum = Duration::from_secs(0);
     let mut min = None;
     let mut max = None;
@@ -43,3 +44,21 @@ pub fn bench(n: u32, f: fn()) {
         avg
     );
 }
+
+/// Tiny xorshift so randomised tests are reproducible without pulling in a crate
+#[cfg(test)]
+pub struct Rng(pub u64);
+
+#[cfg(test)]
+impl Rng {
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+}